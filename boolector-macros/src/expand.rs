@@ -0,0 +1,212 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{Error, Ident, Result};
+
+use crate::parse::{BinOp, Expr, Input, OpClass, UnOp};
+
+/// Expand the macro input into a block that evaluates to a `NodeRef`.
+pub fn expand(input: Input) -> Result<TokenStream> {
+    let solver = input.solver;
+    let mut expander = Expander {
+        stmts: Vec::new(),
+        next_id: 0,
+        solver: Ident::new("__solver", Span::mixed_site()),
+    };
+
+    let value = expander.value(&input.expr, None)?;
+    let solver_ident = &expander.solver;
+    let stmts = &expander.stmts;
+
+    let result = match value {
+        Value::Owned(tokens) => tokens,
+        Value::Borrowed(tokens) => quote! { ::boolector::NodeRef::clone(#tokens) },
+    };
+
+    Ok(quote! {
+        {
+            let #solver_ident: &::boolector::Solver = &(#solver);
+            #(#stmts)*
+            #result
+        }
+    })
+}
+
+/// Code for a subexpression.
+enum Value {
+    /// Expression of type `NodeRef`.
+    Owned(TokenStream),
+
+    /// Expression that coerces to `&NodeRef`.
+    Borrowed(TokenStream),
+}
+
+struct Expander {
+    stmts: Vec<TokenStream>,
+    next_id: usize,
+    solver: Ident,
+}
+
+impl Expander {
+    /// Bind the value of `expr` to a fresh local holding a `&NodeRef`.
+    ///
+    /// `width` is an expression for the width that unsized constants in
+    /// `expr` should take.
+    fn bind(&mut self, expr: &Expr, width: Option<&TokenStream>) -> Result<Ident> {
+        let value = self.value(expr, width)?;
+        Ok(self.bind_value(value))
+    }
+
+    fn value(&mut self, expr: &Expr, width: Option<&TokenStream>) -> Result<Value> {
+        let solver = &self.solver.clone();
+
+        let value = match expr {
+            Expr::Var(ident) => Value::Borrowed(quote_spanned! { ident.span()=> &#ident }),
+            Expr::Int(value, span) => {
+                let width = width.ok_or_else(|| {
+                    Error::new(*span, "cannot infer the width of this constant; write it as in `8'd3`")
+                })?;
+
+                // The width is often only known at run time, so check there
+                // that the constant is not truncated.
+                let width_ident = Ident::new("__width", Span::mixed_site());
+
+                Value::Owned(quote_spanned! { *span=>
+                    {
+                        let #width_ident: u32 = #width;
+                        assert!(
+                            #width_ident >= 64 || #value >> #width_ident == 0,
+                            "constant {} does not fit in {} bits",
+                            #value,
+                            #width_ident,
+                        );
+                        ::boolector::BitVecValue::from_u64(#width_ident, #value).to_node(#solver)
+                    }
+                })
+            },
+            Expr::Const(bits, _) => Value::Owned(quote! {
+                ::boolector::BitVecValue::from_bits(#bits).to_node(#solver)
+            }),
+            Expr::Unary(op, operand, _) => {
+                let operand = self.bind(operand, width)?;
+
+                let node = match op {
+                    UnOp::Not => quote! { Not },
+                    UnOp::Neg => quote! { Neg },
+                };
+
+                Value::Owned(quote! { #solver.node(::boolector::Node::#node(#operand)) })
+            },
+            Expr::Binary(op, lhs, rhs, _) => self.binary(*op, lhs, rhs, width)?,
+            Expr::Ite(cond, then, otherwise, _) => {
+                let cond = self.bind(cond, Some(&quote! { 1u32 }))?;
+                let (then, otherwise) = self.bind_pair(then, otherwise, width)?;
+
+                Value::Owned(quote! { #solver.node(::boolector::Node::Cond(#cond, #then, #otherwise)) })
+            },
+            Expr::Slice(operand, upper, lower, _) => {
+                let operand = self.bind_sized(operand)?;
+
+                Value::Owned(quote! { #solver.node(::boolector::Node::Slice(#operand, #upper, #lower)) })
+            },
+            Expr::Concat(items, _) => {
+                let mut items = items.iter();
+                let mut acc = self.bind_sized(items.next().unwrap())?;
+
+                for item in items {
+                    let item = self.bind_sized(item)?;
+                    acc = self.bind_value(Value::Owned(quote! {
+                        #solver.node(::boolector::Node::Concat(#acc, #item))
+                    }));
+                }
+
+                Value::Borrowed(quote! { #acc })
+            },
+        };
+
+        Ok(value)
+    }
+
+    fn binary(&mut self, op: BinOp, lhs: &Expr, rhs: &Expr, width: Option<&TokenStream>) -> Result<Value> {
+        let solver = &self.solver.clone();
+
+        let (a, b) = match op.class() {
+            OpClass::SameWidth => self.bind_pair(lhs, rhs, width)?,
+            OpClass::Compare => self.bind_pair(lhs, rhs, None)?,
+            OpClass::Logic => self.bind_pair(lhs, rhs, Some(&quote! { 1u32 }))?,
+            OpClass::Shift => {
+                let a = self.bind(lhs, width)?;
+                let b = self.bind(rhs, Some(&quote! { ::boolector::NodeRef::width(#a) }))?;
+                (a, b)
+            },
+        };
+
+        let node = match op {
+            BinOp::Shl => return Ok(Value::Owned(quote! { ::boolector::NodeRef::shift_left(#a, #b) })),
+            BinOp::Lshr => return Ok(Value::Owned(quote! { ::boolector::NodeRef::shift_right_logical(#a, #b) })),
+            BinOp::Ashr => return Ok(Value::Owned(quote! { ::boolector::NodeRef::shift_right_arith(#a, #b) })),
+            BinOp::LogicOr | BinOp::Or => quote! { Or },
+            BinOp::LogicAnd | BinOp::And => quote! { And },
+            BinOp::Xor => quote! { Xor },
+            BinOp::Eq => quote! { Eq },
+            BinOp::Ne => quote! { Ne },
+            BinOp::ULt => quote! { ULt },
+            BinOp::ULte => quote! { ULte },
+            BinOp::UGt => quote! { UGt },
+            BinOp::UGte => quote! { UGte },
+            BinOp::SLt => quote! { SLt },
+            BinOp::SLte => quote! { SLte },
+            BinOp::SGt => quote! { SGt },
+            BinOp::SGte => quote! { SGte },
+            BinOp::Add => quote! { Add },
+            BinOp::Sub => quote! { Sub },
+            BinOp::Mul => quote! { Mul },
+            BinOp::UDiv => quote! { UDiv },
+            BinOp::SDiv => quote! { SDiv },
+            BinOp::URem => quote! { URem },
+            BinOp::SRem => quote! { SRem },
+        };
+
+        Ok(Value::Owned(quote! { #solver.node(::boolector::Node::#node(#a, #b)) }))
+    }
+
+    /// Bind two operands of equal width; an unsized operand takes the width
+    /// of the other one, or `width` if both are unsized.
+    fn bind_pair(&mut self, lhs: &Expr, rhs: &Expr, width: Option<&TokenStream>) -> Result<(Ident, Ident)> {
+        if lhs.is_unsized() && !rhs.is_unsized() {
+            let b = self.bind(rhs, None)?;
+            let a = self.bind(lhs, Some(&quote! { ::boolector::NodeRef::width(#b) }))?;
+            return Ok((a, b));
+        }
+
+        let a = self.bind(lhs, width)?;
+
+        let b = if rhs.is_unsized() {
+            self.bind(rhs, Some(&quote! { ::boolector::NodeRef::width(#a) }))?
+        } else {
+            self.bind(rhs, None)?
+        };
+
+        Ok((a, b))
+    }
+
+    fn bind_sized(&mut self, expr: &Expr) -> Result<Ident> {
+        if expr.is_unsized() {
+            return Err(Error::new(expr.span(), "cannot infer the width of this operand; write constants as in `8'd3`"));
+        }
+
+        self.bind(expr, None)
+    }
+
+    /// Bind generated code to a fresh local holding a `&NodeRef`.
+    fn bind_value(&mut self, value: Value) -> Ident {
+        let name = Ident::new(&format!("__bv{}", self.next_id), Span::mixed_site());
+        self.next_id += 1;
+
+        self.stmts.push(match value {
+            Value::Owned(tokens) => quote! { let #name = &#tokens; },
+            Value::Borrowed(tokens) => quote! { let #name: &::boolector::NodeRef<'_> = #tokens; },
+        });
+
+        name
+    }
+}
@@ -0,0 +1,54 @@
+//! Procedural macros for the `boolector` crate, which re-exports them.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use syn::parse_macro_input;
+
+mod expand;
+mod parse;
+
+/// Build an expression node from infix notation.
+///
+/// The first argument is the solver instance, the second an expression over
+/// Rust variables that hold node references:
+///
+/// * `||` `&&` `|` `^` `&` `==` `!=` `+` `-` `*` and unary `!` (or `~`) and
+///   `-` as in C; `&&` and `||` are bitwise like `&` and `|` but bind more
+///   loosely, and are meant for booleans;
+/// * comparisons, division and remainder need explicit signedness: `<u`,
+///   `<=s`, `/u`, `%s` and so on;
+/// * `<<` shifts left, `>>` shifts right logically and `>>a` arithmetically
+///   (the operand must follow `a` directly);
+/// * `x[7:0]` selects bits 7 down to 0 and `x[3]` a single bit;
+/// * `{a, b}` concatenates, with `a` in the upper bits;
+/// * `ite(c, a, b)` selects `a` if the boolean `c` holds and `b` otherwise;
+/// * `8'hff`, `4'b1010`, `8'o17` and `16'd1000` are constants of the given
+///   width; plain integers take the width of the other operand.
+///
+/// The result is a `NodeRef`.  Evaluating it panics if a plain integer does
+/// not fit in the width it takes.
+///
+/// ```
+/// use boolector::{bv, Builder, Sort};
+///
+/// let solver = Builder::new().finish();
+/// let b8 = solver.sort(Sort::BitVec(8));
+/// let x = b8.var(Some("x"));
+/// let y = b8.var(Some("y"));
+///
+/// solver.assert(&bv!(solver, x + y * 2 <u 8'h80 && (x >>a 1) != y));
+/// solver.assert(&bv!(solver, {x[3:0], y[7:4]} == ite(x[7], 8'hff, -y)));
+/// assert!(solver.solve().is_sat());
+///
+/// let overflow = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| bv!(solver, x == 300)));
+/// assert!(overflow.is_err());
+/// ```
+#[proc_macro]
+pub fn bv(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as parse::Input);
+
+    expand::expand(input)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
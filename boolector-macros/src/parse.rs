@@ -0,0 +1,468 @@
+use proc_macro2::Span;
+use syn::ext::IdentExt;
+use syn::parse::discouraged::Speculative;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, parenthesized, token, Error, Ident, Lifetime, LitInt, Token};
+
+/// Macro input: the solver expression followed by a bit vector expression.
+pub struct Input {
+    pub solver: syn::Expr,
+    pub expr: Expr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let solver = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let expr = parse_expr(input, 1)?;
+
+        if !input.is_empty() {
+            return Err(input.error("unexpected token after expression"));
+        }
+
+        Ok(Input { solver, expr })
+    }
+}
+
+/// Expression in the macro language.
+pub enum Expr {
+    /// Rust variable holding a node reference.
+    Var(Ident),
+
+    /// Integer without a width, which takes the width of its context.
+    Int(u64, Span),
+
+    /// Constant with an explicit width, as a string of bits.
+    Const(String, Span),
+
+    Unary(UnOp, Box<Expr>, Span),
+    Binary(BinOp, Box<Expr>, Box<Expr>, Span),
+    Ite(Box<Expr>, Box<Expr>, Box<Expr>, Span),
+    Slice(Box<Expr>, u32, u32, Span),
+    Concat(Vec<Expr>, Span),
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Var(ident) => ident.span(),
+            Expr::Int(_, span)
+            | Expr::Const(_, span)
+            | Expr::Unary(_, _, span)
+            | Expr::Binary(_, _, _, span)
+            | Expr::Ite(_, _, _, span)
+            | Expr::Slice(_, _, _, span)
+            | Expr::Concat(_, span) => *span,
+        }
+    }
+
+    /// Return whether the width of this expression is only known from its
+    /// context.
+    pub fn is_unsized(&self) -> bool {
+        match self {
+            Expr::Int(..) => true,
+            Expr::Unary(_, expr, _) => expr.is_unsized(),
+            Expr::Binary(op, lhs, rhs, _) => match op.class() {
+                OpClass::SameWidth => lhs.is_unsized() && rhs.is_unsized(),
+                OpClass::Shift => lhs.is_unsized(),
+                OpClass::Compare | OpClass::Logic => false,
+            },
+            Expr::Ite(_, then, otherwise, _) => then.is_unsized() && otherwise.is_unsized(),
+            Expr::Var(_) | Expr::Const(..) | Expr::Slice(..) | Expr::Concat(..) => false,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum UnOp {
+    Not,
+    Neg,
+}
+
+#[derive(Copy, Clone)]
+pub enum BinOp {
+    LogicOr,
+    LogicAnd,
+    Or,
+    Xor,
+    And,
+    Eq,
+    Ne,
+    ULt,
+    ULte,
+    UGt,
+    UGte,
+    SLt,
+    SLte,
+    SGt,
+    SGte,
+    Shl,
+    Lshr,
+    Ashr,
+    Add,
+    Sub,
+    Mul,
+    UDiv,
+    SDiv,
+    URem,
+    SRem,
+}
+
+/// How the widths of operands and result relate.
+pub enum OpClass {
+    /// Operands and result have the same width.
+    SameWidth,
+
+    /// Operands have the same width; the result is a boolean.
+    Compare,
+
+    /// Operands and result are booleans.
+    Logic,
+
+    /// The result has the width of the left operand.
+    Shift,
+}
+
+impl BinOp {
+    pub fn class(self) -> OpClass {
+        match self {
+            BinOp::LogicOr | BinOp::LogicAnd => OpClass::Logic,
+            BinOp::Eq
+            | BinOp::Ne
+            | BinOp::ULt
+            | BinOp::ULte
+            | BinOp::UGt
+            | BinOp::UGte
+            | BinOp::SLt
+            | BinOp::SLte
+            | BinOp::SGt
+            | BinOp::SGte => OpClass::Compare,
+            BinOp::Shl | BinOp::Lshr | BinOp::Ashr => OpClass::Shift,
+            _ => OpClass::SameWidth,
+        }
+    }
+
+    /// Binding strength, from 1 (`||`) to 10 (`*`), as in C.
+    fn precedence(self) -> u8 {
+        match self {
+            BinOp::LogicOr => 1,
+            BinOp::LogicAnd => 2,
+            BinOp::Or => 3,
+            BinOp::Xor => 4,
+            BinOp::And => 5,
+            BinOp::Eq | BinOp::Ne => 6,
+            BinOp::ULt | BinOp::ULte | BinOp::UGt | BinOp::UGte => 7,
+            BinOp::SLt | BinOp::SLte | BinOp::SGt | BinOp::SGte => 7,
+            BinOp::Shl | BinOp::Lshr | BinOp::Ashr => 8,
+            BinOp::Add | BinOp::Sub => 9,
+            BinOp::Mul | BinOp::UDiv | BinOp::SDiv | BinOp::URem | BinOp::SRem => 10,
+        }
+    }
+}
+
+/// Parse binary operators of at least the given precedence by precedence
+/// climbing.
+fn parse_expr(input: ParseStream, min_precedence: u8) -> Result<Expr> {
+    let mut lhs = parse_unary(input)?;
+
+    loop {
+        let fork = input.fork();
+
+        let op = match parse_binop(&fork)? {
+            Some(op) if op.precedence() >= min_precedence => op,
+            _ => return Ok(lhs),
+        };
+
+        input.advance_to(&fork);
+
+        let span = lhs.span();
+        let rhs = parse_expr(input, op.precedence() + 1)?;
+        lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs), span);
+    }
+}
+
+fn parse_binop(input: ParseStream) -> Result<Option<BinOp>> {
+    let op = if input.peek(Token![||]) {
+        input.parse::<Token![||]>()?;
+        BinOp::LogicOr
+    } else if input.peek(Token![&&]) {
+        input.parse::<Token![&&]>()?;
+        BinOp::LogicAnd
+    } else if input.peek(Token![|]) {
+        input.parse::<Token![|]>()?;
+        BinOp::Or
+    } else if input.peek(Token![^]) {
+        input.parse::<Token![^]>()?;
+        BinOp::Xor
+    } else if input.peek(Token![&]) {
+        input.parse::<Token![&]>()?;
+        BinOp::And
+    } else if input.peek(Token![==]) {
+        input.parse::<Token![==]>()?;
+        BinOp::Eq
+    } else if input.peek(Token![!=]) {
+        input.parse::<Token![!=]>()?;
+        BinOp::Ne
+    } else if input.peek(Token![<<]) {
+        input.parse::<Token![<<]>()?;
+        BinOp::Shl
+    } else if input.peek(Token![>>]) {
+        input.parse::<Token![>>]>()?;
+
+        if arith_suffix(input) {
+            input.parse::<Ident>()?;
+            BinOp::Ashr
+        } else {
+            BinOp::Lshr
+        }
+    } else if input.peek(Token![<=]) {
+        let token = input.parse::<Token![<=]>()?;
+        signed(input, token.spans[0], "<=", BinOp::ULte, BinOp::SLte)?
+    } else if input.peek(Token![>=]) {
+        let token = input.parse::<Token![>=]>()?;
+        signed(input, token.spans[0], ">=", BinOp::UGte, BinOp::SGte)?
+    } else if input.peek(Token![<]) {
+        let token = input.parse::<Token![<]>()?;
+        signed(input, token.span, "<", BinOp::ULt, BinOp::SLt)?
+    } else if input.peek(Token![>]) {
+        let token = input.parse::<Token![>]>()?;
+        signed(input, token.span, ">", BinOp::UGt, BinOp::SGt)?
+    } else if input.peek(Token![+]) {
+        input.parse::<Token![+]>()?;
+        BinOp::Add
+    } else if input.peek(Token![-]) {
+        input.parse::<Token![-]>()?;
+        BinOp::Sub
+    } else if input.peek(Token![*]) {
+        input.parse::<Token![*]>()?;
+        BinOp::Mul
+    } else if input.peek(Token![/]) {
+        let token = input.parse::<Token![/]>()?;
+        signed(input, token.span, "/", BinOp::UDiv, BinOp::SDiv)?
+    } else if input.peek(Token![%]) {
+        let token = input.parse::<Token![%]>()?;
+        signed(input, token.span, "%", BinOp::URem, BinOp::SRem)?
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(op))
+}
+
+/// Parse the mandatory `u` or `s` suffix of an operator.
+fn signed(input: ParseStream, span: Span, op: &str, unsigned: BinOp, signed: BinOp) -> Result<BinOp> {
+    let message = format!("`{op}` needs explicit signedness: write `{op}u` or `{op}s`", op = op);
+
+    match input.call(Ident::parse_any) {
+        Ok(ref suffix) if suffix == "u" => Ok(unsigned),
+        Ok(ref suffix) if suffix == "s" => Ok(signed),
+        _ => Err(Error::new(span, message)),
+    }
+}
+
+/// Return whether `>>` is followed by the `a` suffix rather than by a
+/// variable named `a`: the suffix must be directly followed by an operand.
+fn arith_suffix(input: ParseStream) -> bool {
+    let fork = input.fork();
+
+    match fork.parse::<Ident>() {
+        Ok(ref ident) if ident == "a" => {},
+        _ => return false,
+    }
+
+    fork.peek(Ident)
+        || fork.peek(LitInt)
+        || fork.peek(token::Paren)
+        || fork.peek(token::Brace)
+        || fork.peek(Token![!])
+        || fork.peek(Token![~])
+}
+
+fn parse_unary(input: ParseStream) -> Result<Expr> {
+    let op = if input.peek(Token![!]) {
+        Some((UnOp::Not, input.parse::<Token![!]>()?.span))
+    } else if input.peek(Token![~]) {
+        Some((UnOp::Not, input.parse::<Token![~]>()?.span))
+    } else if input.peek(Token![-]) {
+        Some((UnOp::Neg, input.parse::<Token![-]>()?.span))
+    } else {
+        None
+    };
+
+    match op {
+        Some((op, span)) => Ok(Expr::Unary(op, Box::new(parse_unary(input)?), span)),
+        None => parse_postfix(input),
+    }
+}
+
+/// Parse an operand followed by any number of slices `[upper:lower]` or bit
+/// selections `[index]`.
+fn parse_postfix(input: ParseStream) -> Result<Expr> {
+    let mut expr = parse_primary(input)?;
+
+    while input.peek(token::Bracket) {
+        let content;
+        let bracket = bracketed!(content in input);
+        let upper = content.parse::<LitInt>()?.base10_parse()?;
+
+        let lower = if content.peek(Token![:]) {
+            content.parse::<Token![:]>()?;
+            content.parse::<LitInt>()?.base10_parse()?
+        } else {
+            upper
+        };
+
+        if !content.is_empty() {
+            return Err(content.error("expected `]`"));
+        }
+
+        if lower > upper {
+            return Err(Error::new(bracket.span.join(), "lower slice bound exceeds upper bound"));
+        }
+
+        let span = expr.span();
+        expr = Expr::Slice(Box::new(expr), upper, lower, span);
+    }
+
+    Ok(expr)
+}
+
+fn parse_primary(input: ParseStream) -> Result<Expr> {
+    if input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        let expr = parse_expr(&content, 1)?;
+
+        if !content.is_empty() {
+            return Err(content.error("expected `)`"));
+        }
+
+        return Ok(expr);
+    }
+
+    if input.peek(token::Brace) {
+        let content;
+        let brace = syn::braced!(content in input);
+        let items = Punctuated::<Expr, Token![,]>::parse_terminated_with(&content, |input| parse_expr(input, 1))?;
+
+        if items.is_empty() {
+            return Err(Error::new(brace.span.join(), "concatenation needs at least one operand"));
+        }
+
+        return Ok(Expr::Concat(items.into_iter().collect(), brace.span.join()));
+    }
+
+    if input.peek(LitInt) {
+        return parse_literal(input);
+    }
+
+    if input.peek(Ident) {
+        let ident = input.parse::<Ident>()?;
+
+        if ident == "ite" && input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let args = Punctuated::<Expr, Token![,]>::parse_terminated_with(&content, |input| parse_expr(input, 1))?;
+
+            if args.len() != 3 {
+                return Err(Error::new(ident.span(), "`ite` takes a condition and two branches"));
+            }
+
+            let mut args = args.into_iter();
+            let cond = args.next().unwrap();
+            let then = args.next().unwrap();
+            let otherwise = args.next().unwrap();
+
+            return Ok(Expr::Ite(Box::new(cond), Box::new(then), Box::new(otherwise), ident.span()));
+        }
+
+        return Ok(Expr::Var(ident));
+    }
+
+    Err(input.error("expected a variable, a constant, `ite`, `(` or `{`"))
+}
+
+/// Parse an integer, optionally followed by a radix and digits as in
+/// `8'hff`, `4'b1010` or `16'd1000`.
+fn parse_literal(input: ParseStream) -> Result<Expr> {
+    let lit = input.parse::<LitInt>()?;
+
+    if !lit.suffix().is_empty() {
+        return Err(Error::new(lit.span(), "integer suffixes are not supported; write the width as in `8'd3`"));
+    }
+
+    if !input.peek(Lifetime) {
+        let value = lit
+            .base10_parse::<u64>()
+            .map_err(|_| Error::new(lit.span(), "constant does not fit in 64 bits; give it a width as in `128'd...`"))?;
+
+        return Ok(Expr::Int(value, lit.span()));
+    }
+
+    let digits = input.parse::<Lifetime>()?;
+    let width: u32 = lit.base10_parse()?;
+    let span = lit.span().join(digits.span()).unwrap_or_else(|| lit.span());
+
+    if width == 0 {
+        return Err(Error::new(lit.span(), "constant width must be positive"));
+    }
+
+    let text = digits.ident.to_string();
+    let (radix, digits_text) = text.split_at(1);
+
+    let bits = match radix {
+        "b" => digits_to_bits(digits_text, 2),
+        "o" => digits_to_bits(digits_text, 8),
+        "d" => digits_to_bits(digits_text, 10),
+        "h" => digits_to_bits(digits_text, 16),
+        _ => None,
+    };
+
+    let bits = bits.ok_or_else(|| {
+        Error::new(span, "expected a constant such as `8'b1010_0101`, `8'o245`, `8'd165` or `8'ha5`")
+    })?;
+
+    let significant = bits.trim_start_matches('0');
+
+    if significant.len() > width as usize {
+        return Err(Error::new(span, format!("constant does not fit in {} bits", width)));
+    }
+
+    Ok(Expr::Const(format!("{:0>width$}", significant, width = width as usize), span))
+}
+
+/// Convert digits in the given radix, with optional `_` separators, to a
+/// binary string, most significant bit first.
+fn digits_to_bits(digits: &str, radix: u32) -> Option<String> {
+    // Little-endian binary digits of the value read so far.
+    let mut bits: Vec<u8> = Vec::new();
+    let mut any = false;
+
+    for ch in digits.chars().filter(|&ch| ch != '_') {
+        let digit = ch.to_digit(radix)?;
+        any = true;
+
+        // bits = bits * radix + digit
+        let mut carry = digit;
+
+        for bit in bits.iter_mut() {
+            let value = u32::from(*bit) * radix + carry;
+            *bit = (value % 2) as u8;
+            carry = value / 2;
+        }
+
+        while carry > 0 {
+            bits.push((carry % 2) as u8);
+            carry /= 2;
+        }
+    }
+
+    if !any {
+        return None;
+    }
+
+    let bits: String = bits.iter().rev().map(|&bit| if bit == 1 { '1' } else { '0' }).collect();
+
+    Some(if bits.is_empty() { "0".to_owned() } else { bits })
+}
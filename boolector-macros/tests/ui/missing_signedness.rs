@@ -0,0 +1,10 @@
+use boolector::{bv, Builder, Sort};
+
+fn main() {
+    let solver = Builder::new().finish();
+    let b8 = solver.sort(Sort::BitVec(8));
+    let x = b8.var(Some("x"));
+    let y = b8.var(Some("y"));
+
+    let _ = bv!(solver, x < y);
+}
@@ -0,0 +1,9 @@
+use boolector::{bv, Builder, Sort};
+
+fn main() {
+    let solver = Builder::new().finish();
+    let b8 = solver.sort(Sort::BitVec(8));
+    let x = b8.var(Some("x"));
+
+    let _ = bv!(solver, x == 4'h1f);
+}
@@ -0,0 +1,76 @@
+use std::ffi::CString;
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use boolector_sys as ffi;
+
+use crate::cfile;
+
+/// Destination of a Boolector API trace.
+///
+/// Boolector does not close trace files set with `boolector_set_trapi`, so the
+/// owner of the `Btor` instance must drop this only after the instance has been
+/// deleted.
+pub(crate) struct ApiTrace {
+    file: *mut libc::FILE,
+    writer: Option<Box<dyn Write + Send>>,
+}
+
+impl ApiTrace {
+    /// Create a trace that is written to a file at `path`.
+    pub(crate) fn create(path: &Path) -> io::Result<Self> {
+        let path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        let file = unsafe {
+            libc::fopen(path.as_ptr(), b"w\0".as_ptr() as *const libc::c_char)
+        };
+
+        if file.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(ApiTrace { file, writer: None })
+    }
+
+    /// Create a trace that is buffered in a temporary file and copied to
+    /// `writer` when the trace is dropped.
+    pub(crate) fn buffered(writer: Box<dyn Write + Send>) -> io::Result<Self> {
+        let file = unsafe { libc::tmpfile() };
+
+        if file.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(ApiTrace { file, writer: Some(writer) })
+    }
+
+    /// Start tracing API calls made on a `Btor` instance.
+    ///
+    /// The instance already exists, so the `new` call that created it is
+    /// written first to keep the trace replayable.
+    pub(crate) unsafe fn attach(&self, btor_ptr: *mut ffi::Btor) {
+        let header = format!("new\nreturn {:p}\n", btor_ptr);
+
+        libc::fwrite(header.as_ptr() as *const libc::c_void, 1, header.len(), self.file);
+        libc::fflush(self.file);
+
+        ffi::boolector_set_trapi(btor_ptr, self.file as *mut ffi::FILE);
+    }
+}
+
+impl Drop for ApiTrace {
+    fn drop(&mut self) {
+        match self.writer.as_mut() {
+            Some(writer) => {
+                let contents = unsafe { cfile::read_and_close(self.file) };
+                let _ = writer.write_all(contents.as_bytes());
+                let _ = writer.flush();
+            },
+            None => unsafe {
+                libc::fclose(self.file);
+            },
+        }
+    }
+}
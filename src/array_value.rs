@@ -0,0 +1,94 @@
+use std::collections::btree_map::{self, BTreeMap};
+use std::collections::BTreeSet;
+
+use crate::BitVecValue;
+
+/// Concrete array value: a default element plus explicitly stored elements.
+///
+/// Two array values are equal if they map every index to the same element,
+/// however they are stored.
+#[derive(Clone, Debug)]
+pub struct ArrayValue {
+    index_width: u32,
+    default: BitVecValue,
+    // Only elements that differ from the default are stored.
+    entries: BTreeMap<BitVecValue, BitVecValue>,
+}
+
+impl ArrayValue {
+    /// Create an array that maps every index of the given width to `default`.
+    pub fn new(index_width: u32, default: BitVecValue) -> Self {
+        assert!(index_width > 0, "bit vector width must be positive");
+
+        ArrayValue {
+            index_width,
+            default,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Return the width of indexes.
+    pub fn index_width(&self) -> u32 {
+        self.index_width
+    }
+
+    /// Return the width of elements.
+    pub fn element_width(&self) -> u32 {
+        self.default.width()
+    }
+
+    /// Return the element for indexes without an explicit element.
+    pub fn default_element(&self) -> &BitVecValue {
+        &self.default
+    }
+
+    /// Return the element at `index`.
+    ///
+    /// Panics if the width of `index` does not match.
+    pub fn get(&self, index: &BitVecValue) -> &BitVecValue {
+        assert_eq!(index.width(), self.index_width, "array index width does not match");
+
+        self.entries.get(index).unwrap_or(&self.default)
+    }
+
+    /// Store `element` at `index`.
+    ///
+    /// Panics if the widths of `index` or `element` do not match.
+    pub fn insert(&mut self, index: BitVecValue, element: BitVecValue) {
+        assert_eq!(index.width(), self.index_width, "array index width does not match");
+        assert_eq!(element.width(), self.element_width(), "array element width does not match");
+
+        if element == self.default {
+            self.entries.remove(&index);
+        } else {
+            self.entries.insert(index, element);
+        }
+    }
+
+    /// Iterate over the elements that differ from the default, ordered by
+    /// index.
+    pub fn entries(&self) -> btree_map::Iter<'_, BitVecValue, BitVecValue> {
+        self.entries.iter()
+    }
+}
+
+impl PartialEq for ArrayValue {
+    fn eq(&self, other: &Self) -> bool {
+        if self.index_width != other.index_width || self.element_width() != other.element_width() {
+            return false;
+        }
+
+        if self.default == other.default {
+            return self.entries == other.entries;
+        }
+
+        // With different defaults, the stored elements must cover every index.
+        let indexes: BTreeSet<_> = self.entries.keys().chain(other.entries.keys()).collect();
+
+        self.index_width < 64
+            && indexes.len() as u64 == 1 << self.index_width
+            && indexes.iter().all(|index| self.get(index) == other.get(index))
+    }
+}
+
+impl Eq for ArrayValue {}
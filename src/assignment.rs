@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+
+use crate::{NodeRef, Value};
+
+/// Values of variables, see [eval](fn.eval.html).
+#[derive(Clone, Debug, Default)]
+pub struct Assignment {
+    values: HashMap<i32, Value>,
+}
+
+impl Assignment {
+    /// Create an empty assignment.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Assign a value to a variable.
+    ///
+    /// Panics if the width of a bit vector value does not match the variable.
+    pub fn set<V: Into<Value>>(&mut self, var: &NodeRef<'_>, value: V) {
+        let value = value.into();
+
+        if let Value::BitVec(value) = &value {
+            assert_eq!(value.width(), var.width(), "value width does not match variable");
+        }
+
+        self.values.insert(var.id(), value);
+    }
+
+    /// Return the value assigned to a variable.
+    pub fn get(&self, var: &NodeRef<'_>) -> Option<&Value> {
+        self.values.get(&var.id())
+    }
+
+    pub(crate) fn get_by_id(&self, id: i32) -> Option<&Value> {
+        self.values.get(&id)
+    }
+}
@@ -0,0 +1,441 @@
+use std::cmp::Ordering;
+use std::ffi::CString;
+use std::fmt;
+
+use boolector_sys as ffi;
+
+use crate::{NodeRef, Solver};
+
+/// Concrete bit vector value of arbitrary width.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BitVecValue {
+    width: u32,
+    // Little-endian 64-bit limbs; bits above `width` are always zero.
+    limbs: Vec<u64>,
+}
+
+impl BitVecValue {
+    /// Create a value with all bits cleared.
+    ///
+    /// Panics if `width` is zero.
+    pub fn zero(width: u32) -> Self {
+        assert!(width > 0, "bit vector width must be positive");
+
+        BitVecValue {
+            width,
+            limbs: vec![0; width.div_ceil(64) as usize],
+        }
+    }
+
+    /// Create a value with all bits set.
+    pub fn ones(width: u32) -> Self {
+        Self::zero(width).not()
+    }
+
+    /// Create a value from the low bits of `value`, extending it with zeros.
+    pub fn from_u64(width: u32, value: u64) -> Self {
+        let mut result = Self::zero(width);
+        result.limbs[0] = value;
+        result.normalize();
+        result
+    }
+
+    /// Create a value from the low bits of `value`, extending it with copies
+    /// of the sign bit.
+    pub fn from_i64(width: u32, value: i64) -> Self {
+        let mut result = Self::zero(width);
+
+        if value < 0 {
+            result.limbs.iter_mut().for_each(|limb| *limb = u64::MAX);
+        }
+
+        result.limbs[0] = value as u64;
+        result.normalize();
+        result
+    }
+
+    /// Parse a string of '0' and '1' characters, most significant bit first,
+    /// such as [BitVecAssignment::to_str](struct.BitVecAssignment.html#method.to_str)
+    /// returns.  Arbitrary bits ('x') are read as zeros.
+    ///
+    /// Panics if the string is empty or contains other characters.
+    pub fn from_bits(bits: &str) -> Self {
+        let mut result = Self::zero(bits.len() as u32);
+
+        for (index, bit) in bits.bytes().rev().enumerate() {
+            match bit {
+                b'0' | b'x' => {},
+                b'1' => result.set_bit(index as u32, true),
+                _ => panic!("unexpected character in bit vector value"),
+            }
+        }
+
+        result
+    }
+
+    /// Return the width in bits.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Return the bit at `index`, counting from the least significant bit.
+    ///
+    /// Panics if `index` is out of range.
+    pub fn bit(&self, index: u32) -> bool {
+        assert!(index < self.width, "bit index out of range");
+
+        self.limbs[(index / 64) as usize] >> (index % 64) & 1 != 0
+    }
+
+    /// Return the value as an unsigned integer, or `None` if it does not fit.
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.limbs[1..].iter().all(|&limb| limb == 0) {
+            Some(self.limbs[0])
+        } else {
+            None
+        }
+    }
+
+    /// Return the value as a signed integer, or `None` if it does not fit.
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.width <= 64 {
+            let shift = 64 - self.width;
+            return Some((self.limbs[0] << shift) as i64 >> shift);
+        }
+
+        let truncated = self.slice(63, 0);
+
+        if truncated.sext(self.width - 64) == *self {
+            Some(truncated.limbs[0] as i64)
+        } else {
+            None
+        }
+    }
+
+    /// Return the value of a 1-bit vector as a boolean, or `None` for other
+    /// widths.
+    pub fn to_bool(&self) -> Option<bool> {
+        if self.width == 1 {
+            Some(self.limbs[0] != 0)
+        } else {
+            None
+        }
+    }
+
+    /// Return a string of '0' and '1' characters, most significant bit first.
+    pub fn to_bits(&self) -> String {
+        (0..self.width)
+            .rev()
+            .map(|index| if self.bit(index) { '1' } else { '0' })
+            .collect()
+    }
+
+    /// Create a constant node with this value.
+    pub fn to_node<'a>(&self, solver: &'a Solver) -> NodeRef<'a> {
+        let bits = CString::new(self.to_bits()).unwrap();
+
+        unsafe {
+            NodeRef::wrap(solver, ffi::boolector_const(solver.btor_ptr(), bits.as_ptr()))
+        }
+    }
+
+    pub(crate) fn from_bool(value: bool) -> Self {
+        Self::from_u64(1, value as u64)
+    }
+
+    pub(crate) fn set_bit(&mut self, index: u32, value: bool) {
+        assert!(index < self.width, "bit index out of range");
+
+        let limb = &mut self.limbs[(index / 64) as usize];
+        let mask = 1 << (index % 64);
+
+        if value {
+            *limb |= mask;
+        } else {
+            *limb &= !mask;
+        }
+    }
+
+    pub(crate) fn msb(&self) -> bool {
+        self.bit(self.width - 1)
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&limb| limb == 0)
+    }
+
+    pub(crate) fn count_ones(&self) -> u32 {
+        self.limbs.iter().map(|limb| limb.count_ones()).sum()
+    }
+
+    /// Return the value as a shift amount, saturating at `u32::MAX`.
+    pub(crate) fn to_shift_amount(&self) -> u32 {
+        self.to_u64().map_or(u32::MAX, |value| value.min(u64::from(u32::MAX)) as u32)
+    }
+
+    pub(crate) fn not(&self) -> Self {
+        self.map(|limb| !limb)
+    }
+
+    pub(crate) fn and(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a & b)
+    }
+
+    pub(crate) fn or(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a | b)
+    }
+
+    pub(crate) fn xor(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a ^ b)
+    }
+
+    /// Add with carry, returning the sum and the carry out of the top bit.
+    pub(crate) fn add_carry(&self, other: &Self, carry: bool) -> (Self, bool) {
+        self.check_width(other);
+
+        let mut result = Self::zero(self.width);
+        let mut carry = carry as u64;
+
+        for (index, (&a, &b)) in self.limbs.iter().zip(&other.limbs).enumerate() {
+            let sum = u128::from(a) + u128::from(b) + u128::from(carry);
+            result.limbs[index] = sum as u64;
+            carry = (sum >> 64) as u64;
+        }
+
+        let top = self.width % 64;
+        let carry_out = if top == 0 {
+            carry != 0
+        } else {
+            result.limbs[result.limbs.len() - 1] >> top != 0
+        };
+
+        result.normalize();
+        (result, carry_out)
+    }
+
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        self.add_carry(other, false).0
+    }
+
+    pub(crate) fn sub(&self, other: &Self) -> Self {
+        self.add_carry(&other.not(), true).0
+    }
+
+    pub(crate) fn neg(&self) -> Self {
+        Self::zero(self.width).sub(self)
+    }
+
+    pub(crate) fn mul(&self, other: &Self) -> Self {
+        self.check_width(other);
+
+        let len = self.limbs.len();
+        let mut result = Self::zero(self.width);
+
+        for i in 0..len {
+            let mut carry = 0u128;
+
+            for j in 0..len - i {
+                let product = u128::from(result.limbs[i + j])
+                    + u128::from(self.limbs[i]) * u128::from(other.limbs[j])
+                    + carry;
+
+                result.limbs[i + j] = product as u64;
+                carry = product >> 64;
+            }
+        }
+
+        result.normalize();
+        result
+    }
+
+    /// Unsigned division and remainder; division by zero yields all ones and
+    /// the dividend.
+    pub(crate) fn udiv_urem(&self, other: &Self) -> (Self, Self) {
+        self.check_width(other);
+
+        if other.is_zero() {
+            return (Self::ones(self.width), self.clone());
+        }
+
+        // The partial remainder needs one extra bit before subtraction.
+        let divisor = other.uext(1);
+        let mut quotient = Self::zero(self.width);
+        let mut remainder = Self::zero(self.width + 1);
+
+        for index in (0..self.width).rev() {
+            remainder = remainder.shl(1);
+            remainder.set_bit(0, self.bit(index));
+
+            if !remainder.ult(&divisor) {
+                remainder = remainder.sub(&divisor);
+                quotient.set_bit(index, true);
+            }
+        }
+
+        (quotient, remainder.slice(self.width - 1, 0))
+    }
+
+    pub(crate) fn eq_value(&self, other: &Self) -> bool {
+        self.check_width(other);
+        self == other
+    }
+
+    pub(crate) fn ult(&self, other: &Self) -> bool {
+        self.check_width(other);
+        self.cmp_unsigned(other) == Ordering::Less
+    }
+
+    pub(crate) fn slt(&self, other: &Self) -> bool {
+        self.check_width(other);
+
+        match (self.msb(), other.msb()) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => self.ult(other),
+        }
+    }
+
+    pub(crate) fn shl(&self, amount: u32) -> Self {
+        self.map_bits(|index| index.checked_sub(amount).map(|source| self.bit(source)).unwrap_or(false))
+    }
+
+    pub(crate) fn lshr(&self, amount: u32) -> Self {
+        self.map_bits(|index| self.bit_or(index.saturating_add(amount), false))
+    }
+
+    pub(crate) fn ashr(&self, amount: u32) -> Self {
+        let sign = self.msb();
+        self.map_bits(|index| self.bit_or(index.saturating_add(amount), sign))
+    }
+
+    /// Concatenate `self` (upper bits) with `other` (lower bits).
+    pub(crate) fn concat(&self, other: &Self) -> Self {
+        let mut result = Self::zero(self.width + other.width);
+
+        for index in 0..result.width {
+            let bit = if index < other.width {
+                other.bit(index)
+            } else {
+                self.bit(index - other.width)
+            };
+
+            result.set_bit(index, bit);
+        }
+
+        result
+    }
+
+    pub(crate) fn slice(&self, upper: u32, lower: u32) -> Self {
+        assert!(lower <= upper && upper < self.width, "slice bounds out of range");
+
+        let mut result = Self::zero(upper - lower + 1);
+
+        for index in 0..result.width {
+            result.set_bit(index, self.bit(lower + index));
+        }
+
+        result
+    }
+
+    pub(crate) fn uext(&self, bits: u32) -> Self {
+        Self::zero(bits.max(1)).concat(self).slice(self.width + bits - 1, 0)
+    }
+
+    pub(crate) fn sext(&self, bits: u32) -> Self {
+        let fill = if self.msb() { Self::ones(bits.max(1)) } else { Self::zero(bits.max(1)) };
+        fill.concat(self).slice(self.width + bits - 1, 0)
+    }
+
+    fn bit_or(&self, index: u32, fill: bool) -> bool {
+        if index < self.width { self.bit(index) } else { fill }
+    }
+
+    fn map<F: Fn(u64) -> u64>(&self, f: F) -> Self {
+        let mut result = BitVecValue {
+            width: self.width,
+            limbs: self.limbs.iter().map(|&limb| f(limb)).collect(),
+        };
+
+        result.normalize();
+        result
+    }
+
+    fn zip<F: Fn(u64, u64) -> u64>(&self, other: &Self, f: F) -> Self {
+        self.check_width(other);
+
+        BitVecValue {
+            width: self.width,
+            limbs: self.limbs.iter().zip(&other.limbs).map(|(&a, &b)| f(a, b)).collect(),
+        }
+    }
+
+    fn map_bits<F: Fn(u32) -> bool>(&self, f: F) -> Self {
+        let mut result = Self::zero(self.width);
+
+        for index in 0..self.width {
+            result.set_bit(index, f(index));
+        }
+
+        result
+    }
+
+    fn cmp_unsigned(&self, other: &Self) -> Ordering {
+        self.limbs.iter().rev().cmp(other.limbs.iter().rev())
+    }
+
+    fn check_width(&self, other: &Self) {
+        assert_eq!(self.width, other.width, "bit vector widths do not match");
+    }
+
+    fn normalize(&mut self) {
+        let top = self.width % 64;
+
+        if top != 0 {
+            let last = self.limbs.len() - 1;
+            self.limbs[last] &= (1 << top) - 1;
+        }
+    }
+}
+
+/// Values are ordered by width first, then as unsigned numbers.
+impl Ord for BitVecValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.width.cmp(&other.width).then_with(|| self.cmp_unsigned(other))
+    }
+}
+
+impl PartialOrd for BitVecValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BitVecValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_bits())
+    }
+}
+
+/// Values are serialized as strings of bits, most significant bit first.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BitVecValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_bits())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BitVecValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = String::deserialize(deserializer)?;
+
+        if bits.is_empty() || !bits.bytes().all(|bit| bit == b'0' || bit == b'1') {
+            return Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(&bits),
+                &"a non-empty string of '0' and '1' characters",
+            ));
+        }
+
+        Ok(BitVecValue::from_bits(&bits))
+    }
+}
@@ -1,12 +1,21 @@
+use std::ffi::CString;
+use std::io::{self, Write};
+use std::path::Path;
 use std::{mem, ptr};
 
 use boolector_sys as ffi;
 
-use crate::{GenerateModel, Solver};
+use crate::api_trace::ApiTrace;
+use crate::{Config, Engine, GenerateModel, LeakCheck, SatSolver, Solver};
 
 /// Solver instance builder.
 pub struct Builder {
     ptr: *mut ffi::Btor,
+    leak_check: LeakCheck,
+    record_solve_times: bool,
+    record_dag: bool,
+    check_model: bool,
+    trace: Option<ApiTrace>,
 }
 
 impl Builder {
@@ -15,18 +24,34 @@ impl Builder {
     /// By default the instance will have these properties:
     ///
     /// * no model generation;
-    /// * non-incremental.
+    /// * non-incremental;
+    /// * no leak check;
+    /// * no recording of solve times;
+    /// * no recording of expression DAGs;
+    /// * no model checking.
     pub fn new() -> Self {
         Builder {
             ptr: unsafe { ffi::boolector_new() },
+            leak_check: LeakCheck::Disabled,
+            record_solve_times: false,
+            record_dag: false,
+            check_model: false,
+            trace: None,
         }
     }
 
     /// Consume the builder and return the constructed instance.
     pub fn finish(mut self) -> Solver {
-        unsafe {
+        let mut solver = unsafe {
             Solver::from_ffi(mem::replace(&mut self.ptr, ptr::null_mut()))
-        }
+        };
+
+        solver.set_leak_check(self.leak_check);
+        solver.set_record_solve_times(self.record_solve_times);
+        solver.set_record_dag(self.record_dag || self.check_model);
+        solver.set_check_model(self.check_model);
+        solver.set_api_trace(self.trace.take());
+        solver
     }
 
     /// Enable model generation.
@@ -41,8 +66,167 @@ impl Builder {
 
         self
     }
+
+    /// Enable incremental solving, which allows several `solve` calls and
+    /// assertion scopes, see [Solver::push](struct.Solver.html#method.push).
+    pub fn incremental(self, enable: bool) -> Self {
+        unsafe {
+            ffi::boolector_set_opt(
+                self.ptr,
+                ffi::BtorOption_BTOR_OPT_INCREMENTAL,
+                enable as u32,
+            );
+        }
+
+        self
+    }
+
+    /// Select the solver engine.
+    pub fn engine(self, engine: Engine) -> Self {
+        unsafe {
+            ffi::boolector_set_opt(self.ptr, ffi::BtorOption_BTOR_OPT_ENGINE, engine as u32);
+        }
+
+        self
+    }
+
+    /// Select the SAT solver backend.
+    pub fn sat_solver(self, sat_solver: SatSolver) -> Self {
+        let name = CString::new(sat_solver.name()).unwrap();
+
+        unsafe {
+            ffi::boolector_set_sat_solver(self.ptr, name.as_ptr());
+        }
+
+        self
+    }
+
+    /// Set the random seed.
+    pub fn seed(self, seed: u32) -> Self {
+        unsafe {
+            ffi::boolector_set_opt(self.ptr, ffi::BtorOption_BTOR_OPT_SEED, seed);
+        }
+
+        self
+    }
+
+    /// Set the rewrite level, from 0 (no rewriting) to 3 (full rewriting).
+    ///
+    /// Panics if `level` is greater than 3.
+    pub fn rewrite_level(self, level: u32) -> Self {
+        assert!(level <= 3, "rewrite level must be at most 3");
+
+        unsafe {
+            ffi::boolector_set_opt(self.ptr, ffi::BtorOption_BTOR_OPT_REWRITE_LEVEL, level);
+        }
+
+        self
+    }
+
+    /// Apply every setting of `config` that is not `None`.
+    pub fn config(mut self, config: &Config) -> Self {
+        if let Some(engine) = config.engine {
+            self = self.engine(engine);
+        }
+
+        if let Some(sat_solver) = config.sat_solver {
+            self = self.sat_solver(sat_solver);
+        }
+
+        if let Some(seed) = config.seed {
+            self = self.seed(seed);
+        }
+
+        if let Some(level) = config.rewrite_level {
+            self = self.rewrite_level(level);
+        }
+
+        self
+    }
+
+    /// Check for leaked references when the solver instance is dropped.
+    pub fn leak_check(mut self, mode: LeakCheck) -> Self {
+        self.leak_check = mode;
+        self
+    }
+
+    /// Keep aggregated wall times of `solve` calls, see
+    /// [Solver::statistics](struct.Solver.html#method.statistics).
+    pub fn record_solve_times(mut self, enable: bool) -> Self {
+        self.record_solve_times = enable;
+        self
+    }
+
+    /// Record the operator and operands of every node created from a
+    /// [Node](enum.Node.html), see
+    /// [NodeRef::kind](struct.NodeRef.html#method.kind).
+    ///
+    /// Asserted expressions are kept as well, see
+    /// [Solver::assertions_to_dot](struct.Solver.html#method.assertions_to_dot).
+    /// Records keep the operands of recorded nodes and the asserted
+    /// expressions alive until the solver instance is dropped.
+    pub fn record_dag(mut self, enable: bool) -> Self {
+        self.record_dag = enable;
+        self
+    }
+
+    /// Check generated models against the asserted expressions.
+    ///
+    /// This records the DAG and keeps every asserted expression so that
+    /// [Solver::solve_checked](struct.Solver.html#method.solve_checked) can
+    /// re-evaluate it in Rust.  Model generation (for asserted expressions,
+    /// unless configured otherwise) is enabled as well.
+    pub fn check_model(mut self, enable: bool) -> Self {
+        if enable {
+            unsafe {
+                if ffi::boolector_get_opt(self.ptr, ffi::BtorOption_BTOR_OPT_MODEL_GEN) == 0 {
+                    ffi::boolector_set_opt(
+                        self.ptr,
+                        ffi::BtorOption_BTOR_OPT_MODEL_GEN,
+                        GenerateModel::Asserted as u32,
+                    );
+                }
+            }
+        }
+
+        self.check_model = enable;
+        self
+    }
+
+    /// Record every subsequent Boolector API call to a trace file at `path`.
+    ///
+    /// Calls made before this one (such as options set on this builder) are
+    /// not recorded, so this should normally be called first.  The trace can
+    /// be re-executed with [replay](fn.replay.html).
+    pub fn trace_api<P: AsRef<Path>>(self, path: P) -> io::Result<Self> {
+        let trace = ApiTrace::create(path.as_ref())?;
+        Ok(self.attach_trace(trace))
+    }
+
+    /// Record every subsequent Boolector API call and write the trace to
+    /// `writer` once the solver instance is dropped.
+    ///
+    /// See [trace_api](#method.trace_api) for details.
+    pub fn trace_api_writer<W: Write + Send + 'static>(self, writer: W) -> io::Result<Self> {
+        let trace = ApiTrace::buffered(Box::new(writer))?;
+        Ok(self.attach_trace(trace))
+    }
+
+    fn attach_trace(mut self, trace: ApiTrace) -> Self {
+        assert!(self.trace.is_none(), "API trace is already enabled");
+
+        unsafe {
+            trace.attach(self.ptr);
+        }
+
+        self.trace = Some(trace);
+        self
+    }
 }
 
+// See the note on `Send` for `Solver`.
+unsafe impl Send for Builder {}
+
 impl Drop for Builder {
     fn drop(&mut self) {
         if !self.ptr.is_null() {
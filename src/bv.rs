@@ -0,0 +1,344 @@
+use std::ops;
+
+use crate::node::{NodeCtor1, NodeCtor2};
+use crate::{BitVecValue, Node, NodeRef, Solver, Sort, SortRef, Unsigned};
+
+/// Bit vector expression with width known at compile time.
+///
+/// Operations on `BV` only accept operands of matching widths, so width
+/// mismatches are type errors instead of Boolector aborts.  Operations whose
+/// result width depends on the operand widths, such as
+/// [concat](#method.concat) and [slice](#method.slice), take the result width
+/// as a parameter and check it when the program is compiled to a binary
+/// (`cargo check` does not evaluate these checks).
+///
+/// Boolean expressions are represented as `BV<1>`.  Arithmetic is modular, and
+/// signedness is chosen by the operation (`ult` versus `slt`); use
+/// [into_unsigned](#method.into_unsigned) to get the operators of
+/// [Unsigned](struct.Unsigned.html).
+///
+/// # Example
+///
+/// ```
+/// use boolector::BV;
+///
+/// let solver = boolector::Builder::new().finish();
+///
+/// let hi: BV<8> = BV::var(&solver, Some("hi"));
+/// let lo: BV<8> = BV::var(&solver, Some("lo"));
+///
+/// let word: BV<16> = hi.concat(&lo);
+/// let low_byte: BV<8> = word.slice::<7, 0, 8>();
+///
+/// solver.assert(&low_byte.eq(&lo));
+/// assert!(solver.solve().is_sat());
+/// ```
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct BV<'a, const N: u32>(NodeRef<'a>);
+
+/// Compile-time checks for the result width of binary width-changing
+/// operations.
+struct Width2<const N: u32, const M: u32, const R: u32>;
+
+impl<const N: u32, const M: u32, const R: u32> Width2<N, M, R> {
+    const CONCAT: () = assert!(
+        N as u64 + M as u64 == R as u64,
+        "concatenation width must be the sum of operand widths",
+    );
+}
+
+/// Compile-time checks for the result width of unary width-changing
+/// operations.
+struct Width1<const N: u32, const R: u32>;
+
+impl<const N: u32, const R: u32> Width1<N, R> {
+    const NONZERO: () = assert!(N > 0, "bit vector width must be positive");
+    const EXTEND: () = assert!(R >= N, "extension must not make bit vector narrower");
+}
+
+/// Compile-time checks for slice bounds.
+struct SliceBounds<const N: u32, const H: u32, const L: u32, const R: u32>;
+
+impl<const N: u32, const H: u32, const L: u32, const R: u32> SliceBounds<N, H, L, R> {
+    const CHECK: () = assert!(
+        L <= H && H < N && R == H - L + 1,
+        "slice bounds must lie within the bit vector and match the result width",
+    );
+}
+
+impl<'a, const N: u32> BV<'a, N> {
+    /// Create a fresh variable of sort `Sort::BitVec(N)` and optionally
+    /// associate a symbol with it.
+    pub fn var(solver: &'a Solver, symbol: Option<&str>) -> Self {
+        BV(Self::sort(solver).var(symbol))
+    }
+
+    /// Create a constant, truncating `value` to `N` bits.
+    pub fn constant(solver: &'a Solver, value: u64) -> Self {
+        let () = Width1::<N, N>::NONZERO;
+
+        BV(BitVecValue::from_u64(N, value).to_node(solver))
+    }
+
+    /// Wrap a node reference, checking its width at runtime.
+    pub fn from_node(node: NodeRef<'a>) -> Self {
+        assert_eq!(node.width(), N, "bit vector width mismatch");
+
+        BV(node)
+    }
+
+    /// Return the bit vector sort of this type.
+    pub fn sort(solver: &'a Solver) -> SortRef<'a> {
+        let () = Width1::<N, N>::NONZERO;
+
+        solver.sort(Sort::BitVec(N))
+    }
+
+    /// Return the underlying node reference.
+    pub fn node(&self) -> &NodeRef<'a> {
+        &self.0
+    }
+
+    /// Consume the wrapper and return the underlying node reference.
+    pub fn into_node(self) -> NodeRef<'a> {
+        self.0
+    }
+
+    /// Consume the wrapper and mark the bit vector as unsigned.
+    pub fn into_unsigned(self) -> Unsigned<'a> {
+        Unsigned(self.0)
+    }
+
+    /// Concatenate `self` (upper bits) with `other` (lower bits).
+    pub fn concat<const M: u32, const R: u32>(&self, other: &BV<'a, M>) -> BV<'a, R> {
+        let () = Width2::<N, M, R>::CONCAT;
+
+        BV(self.apply_2(&other.0, |a, b| Node::Concat(a, b)))
+    }
+
+    /// Extract bits `H` down to `L` (both inclusive).
+    pub fn slice<const H: u32, const L: u32, const R: u32>(&self) -> BV<'a, R> {
+        let () = SliceBounds::<N, H, L, R>::CHECK;
+
+        BV(Node::Slice(&self.0, H, L).into_ref(self.0.solver))
+    }
+
+    /// Zero-extend to `R` bits.
+    pub fn uext<const R: u32>(&self) -> BV<'a, R> {
+        let () = Width1::<N, R>::EXTEND;
+
+        BV(Node::UExt(&self.0, R - N).into_ref(self.0.solver))
+    }
+
+    /// Sign-extend to `R` bits.
+    pub fn sext<const R: u32>(&self) -> BV<'a, R> {
+        let () = Width1::<N, R>::EXTEND;
+
+        BV(Node::SExt(&self.0, R - N).into_ref(self.0.solver))
+    }
+
+    /// Equality.
+    pub fn eq(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::Eq(a, b)))
+    }
+
+    /// Inequality.
+    pub fn ne(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::Ne(a, b)))
+    }
+
+    /// Unsigned less-than comparison.
+    pub fn ult(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::ULt(a, b)))
+    }
+
+    /// Unsigned less-than-or-equal comparison.
+    pub fn ulte(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::ULte(a, b)))
+    }
+
+    /// Unsigned greater-than comparison.
+    pub fn ugt(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::UGt(a, b)))
+    }
+
+    /// Unsigned greater-than-or-equal comparison.
+    pub fn ugte(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::UGte(a, b)))
+    }
+
+    /// Signed less-than comparison.
+    pub fn slt(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::SLt(a, b)))
+    }
+
+    /// Signed less-than-or-equal comparison.
+    pub fn slte(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::SLte(a, b)))
+    }
+
+    /// Signed greater-than comparison.
+    pub fn sgt(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::SGt(a, b)))
+    }
+
+    /// Signed greater-than-or-equal comparison.
+    pub fn sgte(&self, other: &Self) -> BV<'a, 1> {
+        BV(self.apply_2(&other.0, |a, b| Node::SGte(a, b)))
+    }
+
+    /// Unsigned division, returns -1 for division by zero.
+    pub fn udiv(&self, other: &Self) -> Self {
+        BV(self.apply_2(&other.0, |a, b| Node::UDiv(a, b)))
+    }
+
+    /// Unsigned remainder, returns the dividend for division by zero.
+    pub fn urem(&self, other: &Self) -> Self {
+        BV(self.apply_2(&other.0, |a, b| Node::URem(a, b)))
+    }
+
+    /// Signed division.
+    pub fn sdiv(&self, other: &Self) -> Self {
+        BV(self.apply_2(&other.0, |a, b| Node::SDiv(a, b)))
+    }
+
+    /// Signed remainder (sign matches the dividend sign).
+    pub fn srem(&self, other: &Self) -> Self {
+        BV(self.apply_2(&other.0, |a, b| Node::SRem(a, b)))
+    }
+
+    /// Signed modulo (sign matches the divisor sign).
+    pub fn smod(&self, other: &Self) -> Self {
+        BV(self.apply_2(&other.0, |a, b| Node::SMod(a, b)))
+    }
+
+    /// Shift left by an amount of any width, see
+    /// [NodeRef::shift_left](struct.NodeRef.html#method.shift_left).
+    pub fn shift_left<const M: u32>(&self, amount: &BV<'a, M>) -> Self {
+        BV(self.0.shift_left(&amount.0))
+    }
+
+    /// Logical shift right by an amount of any width.
+    pub fn shift_right_logical<const M: u32>(&self, amount: &BV<'a, M>) -> Self {
+        BV(self.0.shift_right_logical(&amount.0))
+    }
+
+    /// Arithmetic shift right by an amount of any width.
+    pub fn shift_right_arith<const M: u32>(&self, amount: &BV<'a, M>) -> Self {
+        BV(self.0.shift_right_arith(&amount.0))
+    }
+
+    /// Rotate left by a constant amount.
+    pub fn rotate_left_by(&self, amount: u32) -> Self {
+        BV(self.0.rotate_left_by(amount))
+    }
+
+    /// Rotate right by a constant amount.
+    pub fn rotate_right_by(&self, amount: u32) -> Self {
+        BV(self.0.rotate_right_by(amount))
+    }
+
+    #[inline]
+    fn apply_1(&self, f: NodeCtor1<'a>) -> NodeRef<'a> {
+        f(&self.0).into_ref(self.0.solver)
+    }
+
+    #[inline]
+    fn apply_2(&self, other: &NodeRef<'a>, f: NodeCtor2<'a>) -> NodeRef<'a> {
+        f(&self.0, other).into_ref(self.0.solver)
+    }
+}
+
+impl<'a> BV<'a, 1> {
+    /// If-then-else conditional with `self` as the condition.
+    pub fn ite<const M: u32>(&self, then: &BV<'a, M>, otherwise: &BV<'a, M>) -> BV<'a, M> {
+        BV(Node::Cond(&self.0, &then.0, &otherwise.0).into_ref(self.0.solver))
+    }
+
+    /// Implication.
+    pub fn implies(&self, other: &Self) -> Self {
+        let not_self = self.apply_1(|a| Node::Not(a));
+
+        BV(Node::Or(&not_self, &other.0).into_ref(self.0.solver))
+    }
+}
+
+impl<'a, const N: u32> ops::Deref for BV<'a, N> {
+    type Target = NodeRef<'a>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, const N: u32> From<BV<'a, N>> for NodeRef<'a> {
+    fn from(bv: BV<'a, N>) -> Self {
+        bv.0
+    }
+}
+
+impl<'a, const N: u32> ops::Add for &BV<'a, N> {
+    type Output = BV<'a, N>;
+
+    fn add(self, other: Self) -> Self::Output {
+        BV(self.apply_2(&other.0, |a, b| Node::Add(a, b)))
+    }
+}
+
+impl<'a, const N: u32> ops::BitAnd for &BV<'a, N> {
+    type Output = BV<'a, N>;
+
+    fn bitand(self, other: Self) -> Self::Output {
+        BV(self.apply_2(&other.0, |a, b| Node::And(a, b)))
+    }
+}
+
+impl<'a, const N: u32> ops::BitOr for &BV<'a, N> {
+    type Output = BV<'a, N>;
+
+    fn bitor(self, other: Self) -> Self::Output {
+        BV(self.apply_2(&other.0, |a, b| Node::Or(a, b)))
+    }
+}
+
+impl<'a, const N: u32> ops::BitXor for &BV<'a, N> {
+    type Output = BV<'a, N>;
+
+    fn bitxor(self, other: Self) -> Self::Output {
+        BV(self.apply_2(&other.0, |a, b| Node::Xor(a, b)))
+    }
+}
+
+impl<'a, const N: u32> ops::Mul for &BV<'a, N> {
+    type Output = BV<'a, N>;
+
+    fn mul(self, other: Self) -> Self::Output {
+        BV(self.apply_2(&other.0, |a, b| Node::Mul(a, b)))
+    }
+}
+
+impl<'a, const N: u32> ops::Neg for &BV<'a, N> {
+    type Output = BV<'a, N>;
+
+    fn neg(self) -> Self::Output {
+        BV(self.apply_1(|a| Node::Neg(a)))
+    }
+}
+
+impl<'a, const N: u32> ops::Not for &BV<'a, N> {
+    type Output = BV<'a, N>;
+
+    fn not(self) -> Self::Output {
+        BV(self.apply_1(|a| Node::Not(a)))
+    }
+}
+
+impl<'a, const N: u32> ops::Sub for &BV<'a, N> {
+    type Output = BV<'a, N>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        BV(self.apply_2(&other.0, |a, b| Node::Sub(a, b)))
+    }
+}
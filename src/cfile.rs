@@ -0,0 +1,125 @@
+//! Helpers for Boolector functions that write to C streams.
+
+use std::io::{self, Write};
+use std::ptr;
+use std::sync::Mutex;
+
+/// Serializes redirections of the process-wide standard output.
+static STDOUT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Run `f` with standard output redirected into a buffer and return whatever
+/// was written to it.
+///
+/// Boolector prints some information (such as statistics) only to standard
+/// output, so the redirection swaps file descriptor 1 for the whole process.
+/// Rust's standard output stays locked meanwhile, so `print!` from other
+/// threads waits; output that other threads write through C streams or the
+/// raw descriptor while `f` is running is captured as well.
+///
+/// Returns an error, without running `f`, if the redirection cannot be set up.
+pub(crate) fn capture_stdout<F: FnOnce()>(f: F) -> io::Result<String> {
+    let _guard = STDOUT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut stdout = io::stdout().lock();
+    stdout.flush()?;
+
+    unsafe {
+        let file = libc::tmpfile();
+
+        if file.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        libc::fflush(ptr::null_mut());
+
+        let saved_fd = libc::dup(libc::STDOUT_FILENO);
+
+        if saved_fd == -1 {
+            let error = io::Error::last_os_error();
+            libc::fclose(file);
+            return Err(error);
+        }
+
+        if libc::dup2(libc::fileno(file), libc::STDOUT_FILENO) == -1 {
+            let error = io::Error::last_os_error();
+            libc::close(saved_fd);
+            libc::fclose(file);
+            return Err(error);
+        }
+
+        f();
+
+        libc::fflush(ptr::null_mut());
+        if libc::dup2(saved_fd, libc::STDOUT_FILENO) == -1 {
+            panic!("cannot restore standard output: {}", io::Error::last_os_error());
+        }
+
+        libc::close(saved_fd);
+
+        Ok(read_and_close(file))
+    }
+}
+
+/// Read the whole contents of a C stream from the beginning and close it.
+pub(crate) unsafe fn read_and_close(file: *mut libc::FILE) -> String {
+    let mut contents = Vec::new();
+    let mut buf = [0u8; 4096];
+
+    libc::fflush(file);
+    libc::rewind(file);
+
+    loop {
+        let len = libc::fread(buf.as_mut_ptr() as *mut libc::c_void, 1, buf.len(), file);
+
+        if len == 0 {
+            break;
+        }
+
+        contents.extend_from_slice(&buf[..len]);
+    }
+
+    libc::fclose(file);
+
+    String::from_utf8_lossy(&contents).into_owned()
+}
+
+/// Read at most `limit` bytes of a C stream from the beginning and close it;
+/// the flag tells whether the stream held more.
+pub(crate) unsafe fn read_prefix_and_close(file: *mut libc::FILE, limit: usize) -> (String, bool) {
+    let mut contents = vec![0u8; limit + 1];
+    let mut len = 0;
+
+    libc::fflush(file);
+    libc::rewind(file);
+
+    while len < contents.len() {
+        let read = libc::fread(contents[len..].as_mut_ptr() as *mut libc::c_void, 1, contents.len() - len, file);
+
+        if read == 0 {
+            break;
+        }
+
+        len += read;
+    }
+
+    libc::fclose(file);
+
+    let cut = len > limit;
+    contents.truncate(len.min(limit));
+
+    (String::from_utf8_lossy(&contents).into_owned(), cut)
+}
+
+/// Open a temporary C stream that reads `contents` from the beginning, or
+/// return a null pointer if no temporary file can be created.
+pub(crate) unsafe fn open_str(contents: &str) -> *mut libc::FILE {
+    let file = libc::tmpfile();
+
+    if !file.is_null() {
+        libc::fwrite(contents.as_ptr() as *const libc::c_void, 1, contents.len(), file);
+        libc::fflush(file);
+        libc::rewind(file);
+    }
+
+    file
+}
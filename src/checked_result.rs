@@ -0,0 +1,12 @@
+use crate::SolveResult;
+
+/// Result of [Solver::solve_checked](struct.Solver.html#method.solve_checked)
+/// when no assertion is violated.
+pub struct CheckedResult<'a> {
+    /// Result of the `solve` call.
+    pub result: SolveResult<'a>,
+
+    /// Positions of the assertions that could not be checked, because they
+    /// read arrays or apply uninterpreted functions.
+    pub unchecked: Vec<usize>,
+}
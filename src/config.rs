@@ -0,0 +1,21 @@
+use crate::{Engine, SatSolver};
+
+/// Solver configuration, see [Builder::config](struct.Builder.html#method.config).
+///
+/// Settings left as `None` keep Boolector's defaults, which depend on the
+/// backends compiled into the linked library; `Config::default()` sets
+/// nothing.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Config {
+    /// Solver engine.
+    pub engine: Option<Engine>,
+
+    /// SAT solver backend.
+    pub sat_solver: Option<SatSolver>,
+
+    /// Random seed.
+    pub seed: Option<u32>,
+
+    /// Rewrite level, from 0 (none) to 3 (full).
+    pub rewrite_level: Option<u32>,
+}
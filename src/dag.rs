@@ -0,0 +1,548 @@
+use std::collections::{HashMap, HashSet};
+
+use boolector_sys as ffi;
+
+use crate::{Node, NodeRef, Solver};
+
+/// Operator of a [Node](enum.Node.html) without its expression operands.
+///
+/// Each variant stands for the `Node` variant of the same name; operands that
+/// are not expressions, such as extension widths and slice bounds, are kept.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Op {
+    Add,
+    And,
+    Concat,
+    Cond,
+    Dec,
+    Eq,
+    Iff,
+    Inc,
+    Mul,
+    NAnd,
+    Ne,
+    Neg,
+    NOr,
+    Not,
+    Or,
+    RedAnd,
+    RedOr,
+    RedXor,
+    Rol,
+    Ror,
+    SAddO,
+    SDiv,
+    SDivO,
+    SExt(u32),
+    SGt,
+    SGte,
+    Slice(u32, u32),
+    Sll,
+    SLt,
+    SLte,
+    SMod,
+    SMulO,
+    Sra,
+    SRem,
+    Srl,
+    SSubO,
+    Sub,
+    UAddO,
+    UDiv,
+    UExt(u32),
+    UGt,
+    UGte,
+    ULt,
+    ULte,
+    UMulO,
+    URem,
+    USubO,
+    XNOr,
+    Xor,
+}
+
+impl Op {
+    /// Return the number of expression operands.
+    pub fn arity(self) -> usize {
+        match self {
+            Op::Dec
+            | Op::Inc
+            | Op::Neg
+            | Op::Not
+            | Op::RedAnd
+            | Op::RedOr
+            | Op::RedXor
+            | Op::SExt(..)
+            | Op::Slice(..)
+            | Op::UExt(..) => 1,
+            Op::Add
+            | Op::And
+            | Op::Concat
+            | Op::Eq
+            | Op::Iff
+            | Op::Mul
+            | Op::NAnd
+            | Op::Ne
+            | Op::NOr
+            | Op::Or
+            | Op::Rol
+            | Op::Ror
+            | Op::SAddO
+            | Op::SDiv
+            | Op::SDivO
+            | Op::SGt
+            | Op::SGte
+            | Op::Sll
+            | Op::SLt
+            | Op::SLte
+            | Op::SMod
+            | Op::SMulO
+            | Op::Sra
+            | Op::SRem
+            | Op::Srl
+            | Op::SSubO
+            | Op::Sub
+            | Op::UAddO
+            | Op::UDiv
+            | Op::UGt
+            | Op::UGte
+            | Op::ULt
+            | Op::ULte
+            | Op::UMulO
+            | Op::URem
+            | Op::USubO
+            | Op::XNOr
+            | Op::Xor => 2,
+            Op::Cond => 3,
+        }
+    }
+
+    /// Build a node description from this operator and the given operands.
+    ///
+    /// Panics if the number of operands does not match the arity.
+    pub fn to_node<'a, 'b>(self, args: &'b [NodeRef<'a>]) -> Node<'a, 'b> {
+        assert_eq!(args.len(), self.arity(), "wrong number of operands for {:?}", self);
+
+        match self {
+            Op::Add => Node::Add(&args[0], &args[1]),
+            Op::And => Node::And(&args[0], &args[1]),
+            Op::Concat => Node::Concat(&args[0], &args[1]),
+            Op::Cond => Node::Cond(&args[0], &args[1], &args[2]),
+            Op::Dec => Node::Dec(&args[0]),
+            Op::Eq => Node::Eq(&args[0], &args[1]),
+            Op::Iff => Node::Iff(&args[0], &args[1]),
+            Op::Inc => Node::Inc(&args[0]),
+            Op::Mul => Node::Mul(&args[0], &args[1]),
+            Op::NAnd => Node::NAnd(&args[0], &args[1]),
+            Op::Ne => Node::Ne(&args[0], &args[1]),
+            Op::Neg => Node::Neg(&args[0]),
+            Op::NOr => Node::NOr(&args[0], &args[1]),
+            Op::Not => Node::Not(&args[0]),
+            Op::Or => Node::Or(&args[0], &args[1]),
+            Op::RedAnd => Node::RedAnd(&args[0]),
+            Op::RedOr => Node::RedOr(&args[0]),
+            Op::RedXor => Node::RedXor(&args[0]),
+            Op::Rol => Node::Rol(&args[0], &args[1]),
+            Op::Ror => Node::Ror(&args[0], &args[1]),
+            Op::SAddO => Node::SAddO(&args[0], &args[1]),
+            Op::SDiv => Node::SDiv(&args[0], &args[1]),
+            Op::SDivO => Node::SDivO(&args[0], &args[1]),
+            Op::SExt(width) => Node::SExt(&args[0], width),
+            Op::SGt => Node::SGt(&args[0], &args[1]),
+            Op::SGte => Node::SGte(&args[0], &args[1]),
+            Op::Slice(upper, lower) => Node::Slice(&args[0], upper, lower),
+            Op::Sll => Node::Sll(&args[0], &args[1]),
+            Op::SLt => Node::SLt(&args[0], &args[1]),
+            Op::SLte => Node::SLte(&args[0], &args[1]),
+            Op::SMod => Node::SMod(&args[0], &args[1]),
+            Op::SMulO => Node::SMulO(&args[0], &args[1]),
+            Op::Sra => Node::Sra(&args[0], &args[1]),
+            Op::SRem => Node::SRem(&args[0], &args[1]),
+            Op::Srl => Node::Srl(&args[0], &args[1]),
+            Op::SSubO => Node::SSubO(&args[0], &args[1]),
+            Op::Sub => Node::Sub(&args[0], &args[1]),
+            Op::UAddO => Node::UAddO(&args[0], &args[1]),
+            Op::UDiv => Node::UDiv(&args[0], &args[1]),
+            Op::UExt(width) => Node::UExt(&args[0], width),
+            Op::UGt => Node::UGt(&args[0], &args[1]),
+            Op::UGte => Node::UGte(&args[0], &args[1]),
+            Op::ULt => Node::ULt(&args[0], &args[1]),
+            Op::ULte => Node::ULte(&args[0], &args[1]),
+            Op::UMulO => Node::UMulO(&args[0], &args[1]),
+            Op::URem => Node::URem(&args[0], &args[1]),
+            Op::USubO => Node::USubO(&args[0], &args[1]),
+            Op::XNOr => Node::XNOr(&args[0], &args[1]),
+            Op::Xor => Node::Xor(&args[0], &args[1]),
+        }
+    }
+}
+
+impl<'a, 'b> Node<'a, 'b> {
+    /// Return the operator of this node description.
+    pub fn op(&self) -> Op {
+        match *self {
+            Node::Add(..) => Op::Add,
+            Node::And(..) => Op::And,
+            Node::Concat(..) => Op::Concat,
+            Node::Cond(..) => Op::Cond,
+            Node::Dec(..) => Op::Dec,
+            Node::Eq(..) => Op::Eq,
+            Node::Iff(..) => Op::Iff,
+            Node::Inc(..) => Op::Inc,
+            Node::Mul(..) => Op::Mul,
+            Node::NAnd(..) => Op::NAnd,
+            Node::Ne(..) => Op::Ne,
+            Node::Neg(..) => Op::Neg,
+            Node::NOr(..) => Op::NOr,
+            Node::Not(..) => Op::Not,
+            Node::Or(..) => Op::Or,
+            Node::RedAnd(..) => Op::RedAnd,
+            Node::RedOr(..) => Op::RedOr,
+            Node::RedXor(..) => Op::RedXor,
+            Node::Rol(..) => Op::Rol,
+            Node::Ror(..) => Op::Ror,
+            Node::SAddO(..) => Op::SAddO,
+            Node::SDiv(..) => Op::SDiv,
+            Node::SDivO(..) => Op::SDivO,
+            Node::SExt(_, width) => Op::SExt(width),
+            Node::SGt(..) => Op::SGt,
+            Node::SGte(..) => Op::SGte,
+            Node::Slice(_, upper, lower) => Op::Slice(upper, lower),
+            Node::Sll(..) => Op::Sll,
+            Node::SLt(..) => Op::SLt,
+            Node::SLte(..) => Op::SLte,
+            Node::SMod(..) => Op::SMod,
+            Node::SMulO(..) => Op::SMulO,
+            Node::Sra(..) => Op::Sra,
+            Node::SRem(..) => Op::SRem,
+            Node::Srl(..) => Op::Srl,
+            Node::SSubO(..) => Op::SSubO,
+            Node::Sub(..) => Op::Sub,
+            Node::UAddO(..) => Op::UAddO,
+            Node::UDiv(..) => Op::UDiv,
+            Node::UExt(_, width) => Op::UExt(width),
+            Node::UGt(..) => Op::UGt,
+            Node::UGte(..) => Op::UGte,
+            Node::ULt(..) => Op::ULt,
+            Node::ULte(..) => Op::ULte,
+            Node::UMulO(..) => Op::UMulO,
+            Node::URem(..) => Op::URem,
+            Node::USubO(..) => Op::USubO,
+            Node::XNOr(..) => Op::XNOr,
+            Node::Xor(..) => Op::Xor,
+        }
+    }
+
+    /// Return the expression operands of this node description.
+    pub fn args(&self) -> Vec<&'b NodeRef<'a>> {
+        match *self {
+            Node::Dec(a)
+            | Node::Inc(a)
+            | Node::Neg(a)
+            | Node::Not(a)
+            | Node::RedAnd(a)
+            | Node::RedOr(a)
+            | Node::RedXor(a) => vec![a],
+            Node::Add(a, b)
+            | Node::And(a, b)
+            | Node::Concat(a, b)
+            | Node::Eq(a, b)
+            | Node::Iff(a, b)
+            | Node::Mul(a, b)
+            | Node::NAnd(a, b)
+            | Node::Ne(a, b)
+            | Node::NOr(a, b)
+            | Node::Or(a, b)
+            | Node::Rol(a, b)
+            | Node::Ror(a, b)
+            | Node::SAddO(a, b)
+            | Node::SDiv(a, b)
+            | Node::SDivO(a, b)
+            | Node::SGt(a, b)
+            | Node::SGte(a, b)
+            | Node::Sll(a, b)
+            | Node::SLt(a, b)
+            | Node::SLte(a, b)
+            | Node::SMod(a, b)
+            | Node::SMulO(a, b)
+            | Node::Sra(a, b)
+            | Node::SRem(a, b)
+            | Node::Srl(a, b)
+            | Node::SSubO(a, b)
+            | Node::Sub(a, b)
+            | Node::UAddO(a, b)
+            | Node::UDiv(a, b)
+            | Node::UGt(a, b)
+            | Node::UGte(a, b)
+            | Node::ULt(a, b)
+            | Node::ULte(a, b)
+            | Node::UMulO(a, b)
+            | Node::URem(a, b)
+            | Node::USubO(a, b)
+            | Node::XNOr(a, b)
+            | Node::Xor(a, b) => vec![a, b],
+            Node::Cond(a, b, c) => vec![a, b, c],
+            Node::SExt(a, ..)
+            | Node::Slice(a, ..)
+            | Node::UExt(a, ..) => vec![a],
+        }
+    }
+}
+
+/// Operator and operands a node was created from, see
+/// [NodeRef::kind](struct.NodeRef.html#method.kind).
+#[derive(Clone)]
+pub struct NodeKind<'a> {
+    op: Op,
+    args: Vec<NodeRef<'a>>,
+}
+
+impl<'a> NodeKind<'a> {
+    /// Return the operator.
+    pub fn op(&self) -> Op {
+        self.op
+    }
+
+    /// Return the expression operands.
+    pub fn args(&self) -> &[NodeRef<'a>] {
+        &self.args
+    }
+
+    /// Return the node description, which creates an equivalent node.
+    pub fn to_node(&self) -> Node<'a, '_> {
+        self.op.to_node(&self.args)
+    }
+}
+
+/// Callback for [NodeRef::walk](struct.NodeRef.html#method.walk).
+///
+/// ```
+/// use boolector::{Builder, NodeKind, NodeRef, Op, Sort, Visitor};
+///
+/// struct CountAdds(usize);
+///
+/// impl<'a> Visitor<'a> for CountAdds {
+///     fn visit(&mut self, _node: &NodeRef<'a>, kind: Option<&NodeKind<'a>>) {
+///         if kind.map(|kind| kind.op()) == Some(Op::Add) {
+///             self.0 += 1;
+///         }
+///     }
+/// }
+///
+/// let solver = Builder::new().record_dag(true).finish();
+/// let b8 = solver.sort(Sort::BitVec(8));
+/// let x = b8.var(Some("x"));
+/// let y = b8.var(Some("y"));
+///
+/// let sum = &(&x + &y) * &(&x + &y);
+/// assert_eq!(sum.kind().unwrap().op(), Op::Mul);
+///
+/// let mut count = CountAdds(0);
+/// sum.walk(&mut count);
+/// assert_eq!(count.0, 1);
+/// ```
+pub trait Visitor<'a> {
+    /// Visit a node after all of its recorded operands.  `kind` is `None` for
+    /// nodes without a record, such as variables and constants.
+    fn visit(&mut self, node: &NodeRef<'a>, kind: Option<&NodeKind<'a>>);
+}
+
+/// Bottom-up computation for [NodeRef::fold](struct.NodeRef.html#method.fold).
+///
+/// Results for shared operands are computed once and cloned.
+///
+/// ```
+/// use boolector::{Builder, Fold, NodeKind, NodeRef, Op, Sort};
+///
+/// // Replace every addition with a subtraction.
+/// struct AddToSub;
+///
+/// impl<'a> Fold<'a> for AddToSub {
+///     type Output = NodeRef<'a>;
+///
+///     fn fold_leaf(&mut self, node: &NodeRef<'a>) -> NodeRef<'a> {
+///         node.clone()
+///     }
+///
+///     fn fold_node(&mut self, node: &NodeRef<'a>, kind: &NodeKind<'a>, args: Vec<NodeRef<'a>>) -> NodeRef<'a> {
+///         let op = if kind.op() == Op::Add { Op::Sub } else { kind.op() };
+///         op.to_node(&args).into_ref(node.solver())
+///     }
+/// }
+///
+/// let solver = Builder::new().record_dag(true).finish();
+/// let b8 = solver.sort(Sort::BitVec(8));
+/// let x = b8.var(Some("x"));
+/// let y = b8.var(Some("y"));
+///
+/// let expr = (&x + &y) & &x;
+/// let rewritten = expr.fold(&mut AddToSub);
+/// assert_eq!(rewritten.id(), ((&x - &y) & &x).id());
+/// ```
+pub trait Fold<'a> {
+    /// Result type.
+    type Output: Clone;
+
+    /// Compute the result for a node without a record, such as a variable or
+    /// a constant.
+    fn fold_leaf(&mut self, node: &NodeRef<'a>) -> Self::Output;
+
+    /// Compute the result for a node from the results for its operands.
+    fn fold_node(&mut self, node: &NodeRef<'a>, kind: &NodeKind<'a>, args: Vec<Self::Output>) -> Self::Output;
+}
+
+impl<'a> NodeRef<'a> {
+    /// Return the operator and operands this node was created from.
+    ///
+    /// Recording is enabled with
+    /// [Builder::record_dag](struct.Builder.html#method.record_dag) and covers
+    /// every node created from a [Node](enum.Node.html), including nodes
+    /// created by operators and helper methods.  Returns `None` if recording
+    /// is disabled, and for nodes that were not
+    /// created from a `Node`, such as variables and constants.  If Boolector
+    /// simplifies a new node to an existing one, the existing node keeps its
+    /// own record, if any.
+    pub fn kind(&self) -> Option<NodeKind<'a>> {
+        self.solver.node_kind(self)
+    }
+
+    /// Visit every node reachable from this one through recorded operands,
+    /// each exactly once, operands before the nodes that use them.
+    pub fn walk<V: Visitor<'a>>(&self, visitor: &mut V) {
+        for (node, kind) in self.post_order() {
+            visitor.visit(&node, kind.as_ref());
+        }
+    }
+
+    /// Compute a result bottom-up over the recorded DAG below this node.
+    pub fn fold<F: Fold<'a>>(&self, folder: &mut F) -> F::Output {
+        let mut results: HashMap<i32, F::Output> = HashMap::new();
+        let mut result = None;
+
+        for (node, kind) in self.post_order() {
+            let output = match kind {
+                Some(kind) => {
+                    let args = kind.args.iter().map(|arg| results[&arg.id()].clone()).collect();
+                    folder.fold_node(&node, &kind, args)
+                },
+                None => folder.fold_leaf(&node),
+            };
+
+            results.insert(node.id(), output.clone());
+            result = Some(output);
+        }
+
+        result.expect("traversal always yields the root node")
+    }
+
+    /// List reachable nodes in post-order without recursion.
+    fn post_order(&self) -> Vec<(NodeRef<'a>, Option<NodeKind<'a>>)> {
+        let mut order = Vec::new();
+        let mut seen = HashSet::new();
+        let mut stack = vec![(self.clone(), self.kind(), 0)];
+
+        seen.insert(self.id());
+
+        while let Some((_, kind, next)) = stack.last_mut() {
+            let arg = kind.as_ref().and_then(|kind| kind.args.get(*next)).cloned();
+            *next += 1;
+
+            match arg {
+                Some(arg) => {
+                    if seen.insert(arg.id()) {
+                        let kind = arg.kind();
+                        stack.push((arg, kind, 0));
+                    }
+                },
+                None => {
+                    let (node, kind, _) = stack.pop().unwrap();
+                    order.push((node, kind));
+                },
+            }
+        }
+
+        order
+    }
+}
+
+/// Recorded operators and operands, keyed by node id.
+///
+/// Records hold a reference to each operand, so that operands stay alive and
+/// can be handed out again.  A node is only recorded if it ranks above all of
+/// its operands (see `rank`), which keeps the records acyclic even when
+/// Boolector simplifies a node to one of its own operands.
+pub(crate) struct Dag {
+    records: HashMap<i32, Record>,
+    refs: u32,
+}
+
+struct Record {
+    op: Op,
+    args: Vec<*mut ffi::BoolectorNode>,
+}
+
+impl Dag {
+    pub(crate) fn new() -> Self {
+        Dag {
+            records: HashMap::new(),
+            refs: 0,
+        }
+    }
+
+    /// Return the number of node references held by the records.
+    pub(crate) fn refs(&self) -> u32 {
+        self.refs
+    }
+
+    /// Record the description a node was created from, unless the node already
+    /// has a record.
+    pub(crate) fn record(&mut self, node: &NodeRef<'_>, desc: &Node<'_, '_>) {
+        let id = node.id();
+        let args = desc.args();
+
+        if self.records.contains_key(&id) || args.iter().any(|arg| rank(arg.id()) >= rank(id)) {
+            return;
+        }
+
+        let btor_ptr = node.solver().btor_ptr();
+        let args: Vec<_> = args
+            .iter()
+            .map(|arg| unsafe { ffi::boolector_copy(btor_ptr, arg.node_ptr()) })
+            .collect();
+
+        self.refs += args.len() as u32;
+        self.records.insert(id, Record { op: desc.op(), args });
+    }
+
+    /// Return the record for a node.
+    pub(crate) fn kind<'a>(&self, solver: &'a Solver, node: &NodeRef<'_>) -> Option<NodeKind<'a>> {
+        self.records.get(&node.id()).map(|record| NodeKind {
+            op: record.op,
+            args: record.args
+                .iter()
+                .map(|&arg| unsafe {
+                    NodeRef::wrap(solver, ffi::boolector_copy(solver.btor_ptr(), arg))
+                })
+                .collect(),
+        })
+    }
+
+    /// Release all references held by the records.
+    pub(crate) unsafe fn release(&mut self, btor_ptr: *mut ffi::Btor) {
+        for (_, record) in self.records.drain() {
+            for arg in record.args {
+                ffi::boolector_release(btor_ptr, arg);
+            }
+        }
+
+        self.refs = 0;
+    }
+}
+
+/// Order nodes so that every node ranks above its operands.
+///
+/// New nodes get ids above all existing ones.  An inverted reference ranks
+/// just above the node it inverts.
+fn rank(id: i32) -> u64 {
+    2 * u64::from(id.unsigned_abs()) + u64::from(id < 0)
+}
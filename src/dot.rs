@@ -0,0 +1,209 @@
+use std::collections::{HashSet, VecDeque};
+use std::ffi::CStr;
+use std::fmt::Write;
+use std::slice;
+
+use boolector_sys as ffi;
+
+use crate::{Model, NodeRef, Solver, Value};
+
+/// Options for Graphviz DOT export, see
+/// [NodeRef::to_dot](struct.NodeRef.html#method.to_dot) and
+/// [Solver::assertions_to_dot](struct.Solver.html#method.assertions_to_dot).
+///
+/// ```
+/// use boolector::{BitVecValue, Builder, DotOptions, GenerateModel, Node, Sort};
+///
+/// let solver = Builder::new()
+///     .generate_model(GenerateModel::All)
+///     .record_dag(true)
+///     .finish();
+///
+/// let b8 = solver.sort(Sort::BitVec(8));
+/// let x = b8.var(Some("x"));
+/// let y = b8.var(Some("y"));
+/// let sum = &(&x + &y) * &(&x + &y);
+/// let bound = BitVecValue::from_u64(8, 4).to_node(&solver);
+/// solver.assert(&solver.node(Node::Eq(&sum, &bound)));
+///
+/// // The shared sum is drawn once, with two edges into it.
+/// let dot = sum.to_dot(&DotOptions::new());
+/// assert_eq!(dot.matches("label=\"Add").count(), 1);
+///
+/// if let boolector::SolveResult::Sat(Some(model)) = solver.solve() {
+///     let dot = solver.assertions_to_dot(&DotOptions::new().max_depth(1).model(&model));
+///     assert!(dot.contains("= 00000100"));
+/// }
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct DotOptions<'m> {
+    max_depth: Option<u32>,
+    model: Option<&'m Model<'m>>,
+}
+
+impl<'m> DotOptions<'m> {
+    /// Create options that draw the whole recorded DAG without values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop expanding operands below the given distance from the roots;
+    /// vertices at the cut are drawn dashed.
+    pub fn max_depth(mut self, depth: u32) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Annotate each vertex with its value in `model`, see
+    /// [Model::eval](struct.Model.html#method.eval).
+    pub fn model(mut self, model: &'m Model<'m>) -> Self {
+        self.model = Some(model);
+        self
+    }
+}
+
+impl<'a> NodeRef<'a> {
+    /// Return a Graphviz DOT graph of the recorded DAG below this node.
+    ///
+    /// There is one vertex per distinct node, labeled with its operator, or
+    /// its symbol or value for leaves, and its width.  Edges are labeled with
+    /// the operand position where the order matters.
+    ///
+    /// Panics unless the instance records its DAG, see
+    /// [Builder::record_dag](struct.Builder.html#method.record_dag).
+    pub fn to_dot(&self, options: &DotOptions<'_>) -> String {
+        assert!(self.solver().records_dag(), "the DAG is not recorded; enable DAG recording");
+        write_dot(slice::from_ref(self), false, options)
+    }
+}
+
+impl Solver {
+    /// Return a Graphviz DOT graph of all asserted expressions, as in
+    /// [NodeRef::to_dot](struct.NodeRef.html#method.to_dot); asserted
+    /// vertices are drawn with a double border.
+    ///
+    /// Panics unless the instance records its DAG, see
+    /// [Builder::record_dag](struct.Builder.html#method.record_dag).
+    pub fn assertions_to_dot(&self, options: &DotOptions<'_>) -> String {
+        let roots = self.assertions().expect("the DAG is not recorded; enable DAG recording");
+        write_dot(&roots, true, options)
+    }
+}
+
+fn write_dot(roots: &[NodeRef<'_>], mark_roots: bool, options: &DotOptions<'_>) -> String {
+    let root_ids: HashSet<i32> = roots.iter().map(NodeRef::id).collect();
+    let mut seen = HashSet::new();
+    let mut queue: VecDeque<(NodeRef<'_>, u32)> = VecDeque::new();
+    let mut out = String::from("digraph {\n");
+
+    for root in roots {
+        if seen.insert(root.id()) {
+            queue.push_back((root.clone(), 0));
+        }
+    }
+
+    // Breadth-first, so that each vertex is reached at its smallest depth.
+    while let Some((node, depth)) = queue.pop_front() {
+        let kind = node.kind();
+        let cut = kind.is_some() && options.max_depth.is_some_and(|max_depth| depth >= max_depth);
+
+        let mut label = match &kind {
+            Some(kind) => format!("{:?}", kind.op()),
+            None => leaf_label(&node),
+        };
+
+        write!(label, "\n[{}]", node.width()).unwrap();
+
+        if let Some(model) = options.model.filter(|_| !is_function(&node)) {
+            write!(label, "\n= {}", format_value(&model.eval(&node))).unwrap();
+        }
+
+        write!(out, "  \"{}\" [label=\"{}\"", node.id(), escape(&label)).unwrap();
+
+        if mark_roots && root_ids.contains(&node.id()) {
+            out.push_str(", peripheries=2");
+        }
+
+        if cut {
+            out.push_str(", style=dashed");
+        }
+
+        out.push_str("];\n");
+
+        let kind = match kind {
+            Some(kind) if !cut => kind,
+            _ => continue,
+        };
+
+        let ordered = kind.args().len() > 1;
+
+        for (position, arg) in kind.args().iter().enumerate() {
+            write!(out, "  \"{}\" -> \"{}\"", node.id(), arg.id()).unwrap();
+
+            if ordered {
+                write!(out, " [label=\"{}\"]", position).unwrap();
+            }
+
+            out.push_str(";\n");
+
+            if seen.insert(arg.id()) {
+                queue.push_back((arg.clone(), depth + 1));
+            }
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Return whether a node is a function other than an array, which has no
+/// value in a model.
+fn is_function(node: &NodeRef<'_>) -> bool {
+    let btor_ptr = node.solver().btor_ptr();
+
+    unsafe { ffi::boolector_is_fun(btor_ptr, node.node_ptr()) && !ffi::boolector_is_array(btor_ptr, node.node_ptr()) }
+}
+
+/// Label a node without a record by its symbol, or its bits if constant.
+fn leaf_label(node: &NodeRef<'_>) -> String {
+    if let Some(symbol) = node.symbol() {
+        return symbol;
+    }
+
+    let btor_ptr = node.solver().btor_ptr();
+
+    unsafe {
+        if ffi::boolector_is_const(btor_ptr, node.node_ptr()) {
+            let bits_ptr = ffi::boolector_get_bits(btor_ptr, node.node_ptr());
+            let bits = CStr::from_ptr(bits_ptr).to_string_lossy().into_owned();
+            ffi::boolector_free_bits(btor_ptr, bits_ptr);
+            return bits;
+        }
+    }
+
+    format!("#{}", node.id())
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::BitVec(value) => value.to_string(),
+        Value::Array(array) => {
+            let mut text = String::from("{");
+
+            for (index, element) in array.entries() {
+                write!(text, "{}: {}, ", index, element).unwrap();
+            }
+
+            write!(text, "_: {}}}", array.default_element()).unwrap();
+            text
+        },
+    }
+}
+
+/// Escape a label for a double-quoted DOT string.
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,16 @@
+/// Solver engine.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(u32)]
+pub enum Engine {
+    /// Lemmas on demand for arrays and uninterpreted functions.
+    Fun = 0,
+
+    /// Stochastic local search.
+    Sls = 1,
+
+    /// Propagation-based local search.
+    Prop = 2,
+
+    /// Propagation-based local search on the AIG level.
+    AigProp = 3,
+}
@@ -0,0 +1,229 @@
+use std::ffi::CStr;
+
+use boolector_sys as ffi;
+
+use crate::{Assignment, BitVecValue, Fold, NodeKind, NodeRef, Op, Value};
+
+/// Compute the value of an expression in Rust, without calling the solver.
+///
+/// Operators and operands are taken from the recorded DAG (see
+/// [Builder::record_dag](struct.Builder.html#method.record_dag)), constants
+/// from the solver instance, and variables from `assignment`.  The result
+/// follows Boolector semantics, including division by zero as documented on
+/// [Node](enum.Node.html).
+///
+/// Panics if a variable has no value in `assignment`, or if the expression
+/// depends on a node that is neither recorded nor a constant.
+///
+/// ```
+/// use boolector::{eval, Assignment, BitVecValue, Builder, Sort};
+///
+/// let solver = Builder::new().record_dag(true).finish();
+/// let b8 = solver.sort(Sort::BitVec(8));
+/// let x = b8.var(Some("x"));
+/// let y = b8.var(Some("y"));
+///
+/// let mut assignment = Assignment::new();
+/// assignment.set(&x, BitVecValue::from_u64(8, 200));
+/// assignment.set(&y, BitVecValue::from_u64(8, 0));
+///
+/// let sum = eval(&((&x + &y) * 2), &assignment);
+/// assert_eq!(sum.as_bit_vec().unwrap().to_u64(), Some(144));
+///
+/// // Unsigned division by zero yields all ones.
+/// let quotient = &boolector::Unsigned(x) / &boolector::Unsigned(y);
+/// let quotient = eval(&quotient, &assignment);
+/// assert_eq!(quotient.as_bit_vec().unwrap().to_u64(), Some(255));
+/// ```
+pub fn eval(expr: &NodeRef<'_>, assignment: &Assignment) -> Value {
+    eval_with(expr, |node| {
+        let id = node.id();
+
+        if let Some(value) = assignment.get_by_id(id) {
+            return value.clone();
+        }
+
+        // Inverted reference to an assigned variable.
+        if let Some(Value::BitVec(value)) = assignment.get_by_id(-id) {
+            return Value::BitVec(value.not());
+        }
+
+        panic!("cannot evaluate node {}: it is not recorded, constant or assigned", id);
+    })
+}
+
+/// Compute the value of an expression in Rust, taking the values of
+/// non-constant leaves from `leaf`.
+pub(crate) fn eval_with<'a, F: FnMut(&NodeRef<'a>) -> Value>(expr: &NodeRef<'a>, leaf: F) -> Value {
+    expr.fold(&mut Evaluator { leaf })
+}
+
+struct Evaluator<F> {
+    leaf: F,
+}
+
+impl<'a, F: FnMut(&NodeRef<'a>) -> Value> Fold<'a> for Evaluator<F> {
+    type Output = Value;
+
+    fn fold_leaf(&mut self, node: &NodeRef<'a>) -> Value {
+        let btor_ptr = node.solver().btor_ptr();
+
+        unsafe {
+            if ffi::boolector_is_const(btor_ptr, node.node_ptr()) {
+                let bits_ptr = ffi::boolector_get_bits(btor_ptr, node.node_ptr());
+                let value = BitVecValue::from_bits(&CStr::from_ptr(bits_ptr).to_string_lossy());
+                ffi::boolector_free_bits(btor_ptr, bits_ptr);
+                return Value::BitVec(value);
+            }
+        }
+
+        (self.leaf)(node)
+    }
+
+    fn fold_node(&mut self, _node: &NodeRef<'a>, kind: &NodeKind<'a>, args: Vec<Value>) -> Value {
+        match kind.op() {
+            Op::Cond => {
+                let mut args = args.into_iter();
+                let cond = bit_vec(args.next().unwrap()).to_bool().expect("condition must be a boolean");
+                let then = args.next().unwrap();
+                let otherwise = args.next().unwrap();
+
+                return if cond { then } else { otherwise };
+            },
+            Op::Eq => return boolean(args[0] == args[1]),
+            Op::Ne => return boolean(args[0] != args[1]),
+            _ => {},
+        }
+
+        let args: Vec<_> = args.into_iter().map(bit_vec).collect();
+        Value::BitVec(apply(kind.op(), &args))
+    }
+}
+
+/// Apply a bit vector operator.
+fn apply(op: Op, args: &[BitVecValue]) -> BitVecValue {
+    let a = &args[0];
+    let b = || &args[1];
+    let flag = BitVecValue::from_bool;
+
+    match op {
+        Op::Add => a.add(b()),
+        Op::And => a.and(b()),
+        Op::Concat => a.concat(b()),
+        Op::Dec => a.sub(&BitVecValue::from_u64(a.width(), 1)),
+        Op::Iff => flag(a.eq_value(b())),
+        Op::Inc => a.add(&BitVecValue::from_u64(a.width(), 1)),
+        Op::Mul => a.mul(b()),
+        Op::NAnd => a.and(b()).not(),
+        Op::Neg => a.neg(),
+        Op::NOr => a.or(b()).not(),
+        Op::Not => a.not(),
+        Op::Or => a.or(b()),
+        Op::RedAnd => flag(a.count_ones() == a.width()),
+        Op::RedOr => flag(!a.is_zero()),
+        Op::RedXor => flag(a.count_ones() % 2 == 1),
+        Op::Rol => rotate_left(a, rotate_amount(a, b())),
+        Op::Ror => rotate_left(a, (a.width() - rotate_amount(a, b())) % a.width()),
+        Op::SAddO => {
+            let sum = a.add(b());
+            flag(a.msb() == b().msb() && sum.msb() != a.msb())
+        },
+        Op::SDiv => sdiv(a, b()),
+        Op::SDivO => flag(a.msb() && a.count_ones() == 1 && b().count_ones() == b().width()),
+        Op::SExt(bits) => a.sext(bits),
+        Op::SGt => flag(b().slt(a)),
+        Op::SGte => flag(!a.slt(b())),
+        Op::Slice(upper, lower) => a.slice(upper, lower),
+        Op::Sll => a.shl(b().to_shift_amount()),
+        Op::SLt => flag(a.slt(b())),
+        Op::SLte => flag(!b().slt(a)),
+        Op::SMod => smod(a, b()),
+        Op::SMulO => {
+            let width = a.width();
+            let product = a.sext(width).mul(&b().sext(width));
+            flag(product.slice(width - 1, 0).sext(width) != product)
+        },
+        Op::Sra => a.ashr(b().to_shift_amount()),
+        Op::SRem => srem(a, b()),
+        Op::Srl => a.lshr(b().to_shift_amount()),
+        Op::SSubO => {
+            let difference = a.sub(b());
+            flag(a.msb() != b().msb() && difference.msb() != a.msb())
+        },
+        Op::Sub => a.sub(b()),
+        Op::UAddO => flag(a.add_carry(b(), false).1),
+        Op::UDiv => a.udiv_urem(b()).0,
+        Op::UExt(bits) => a.uext(bits),
+        Op::UGt => flag(b().ult(a)),
+        Op::UGte => flag(!a.ult(b())),
+        Op::ULt => flag(a.ult(b())),
+        Op::ULte => flag(!b().ult(a)),
+        Op::UMulO => {
+            let width = a.width();
+            let product = a.uext(width).mul(&b().uext(width));
+            flag(!product.slice(2 * width - 1, width).is_zero())
+        },
+        Op::URem => a.udiv_urem(b()).1,
+        Op::USubO => flag(a.ult(b())),
+        Op::XNOr => a.xor(b()).not(),
+        Op::Xor => a.xor(b()),
+        Op::Cond | Op::Eq | Op::Ne => unreachable!(),
+    }
+}
+
+/// Reduce a rotation amount of any width modulo the width of `a`.
+fn rotate_amount(a: &BitVecValue, amount: &BitVecValue) -> u32 {
+    let width = amount.width().max(32);
+    let amount = amount.uext(width - amount.width());
+    let (_, rem) = amount.udiv_urem(&BitVecValue::from_u64(width, u64::from(a.width())));
+
+    rem.to_u64().expect("remainder is below the width") as u32
+}
+
+fn rotate_left(a: &BitVecValue, amount: u32) -> BitVecValue {
+    if amount == 0 {
+        a.clone()
+    } else {
+        a.shl(amount).or(&a.lshr(a.width() - amount))
+    }
+}
+
+fn abs(a: &BitVecValue) -> BitVecValue {
+    if a.msb() { a.neg() } else { a.clone() }
+}
+
+fn sdiv(a: &BitVecValue, b: &BitVecValue) -> BitVecValue {
+    let quotient = abs(a).udiv_urem(&abs(b)).0;
+    if a.msb() != b.msb() { quotient.neg() } else { quotient }
+}
+
+fn srem(a: &BitVecValue, b: &BitVecValue) -> BitVecValue {
+    let remainder = abs(a).udiv_urem(&abs(b)).1;
+    if a.msb() { remainder.neg() } else { remainder }
+}
+
+fn smod(a: &BitVecValue, b: &BitVecValue) -> BitVecValue {
+    let remainder = abs(a).udiv_urem(&abs(b)).1;
+
+    if remainder.is_zero() {
+        return remainder;
+    }
+
+    match (a.msb(), b.msb()) {
+        (false, false) => remainder,
+        (true, false) => remainder.neg().add(b),
+        (false, true) => remainder.add(b),
+        (true, true) => remainder.neg(),
+    }
+}
+
+fn bit_vec(value: Value) -> BitVecValue {
+    match value {
+        Value::BitVec(value) => value,
+        Value::Array(_) => panic!("unexpected array operand"),
+    }
+}
+
+fn boolean(value: bool) -> Value {
+    Value::BitVec(BitVecValue::from_bool(value))
+}
@@ -0,0 +1,16 @@
+/// Leak check mode, applied when a solver instance is dropped.
+///
+/// A reference is leaked when it is still alive at the time its parent solver
+/// is dropped; with the safe API this can only happen to references that were
+/// turned into raw pointers with `into_ffi` and never released.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LeakCheck {
+    /// Do not check for leaked references.
+    Disabled,
+
+    /// Print a report about leaked references to standard error.
+    Report,
+
+    /// Panic if there are leaked references.
+    Panic,
+}
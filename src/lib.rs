@@ -33,26 +33,87 @@
 //! assert!(solver.solve().is_sat());
 //! ```
 
+pub use boolector_macros::bv;
+
+pub use self::array_value::*;
+pub use self::assignment::*;
 pub use self::bit_vec_assignment::*;
+pub use self::bit_vec_value::*;
 pub use self::builder::*;
+pub use self::bv::*;
+pub use self::checked_result::*;
+pub use self::config::*;
+pub use self::dag::*;
+pub use self::dot::*;
+pub use self::engine::*;
+pub use self::eval::*;
 pub use self::generate_model::*;
+pub use self::leak_check::*;
 pub use self::model::*;
+pub use self::model_counter::*;
+pub use self::model_error::*;
+pub use self::model_snapshot::*;
+pub use self::models::*;
 pub use self::node::*;
 pub use self::node_ref::*;
+pub use self::optimize::*;
+pub use self::owned_node_ref::*;
+pub use self::owned_sort_ref::*;
+pub use self::query_cache::*;
+pub use self::replay::*;
+pub use self::sat_solver::*;
+pub use self::shared_solver::*;
 pub use self::solve_result::*;
 pub use self::solver::*;
+pub use self::solver_pool::*;
 pub use self::sort::*;
 pub use self::sort_ref::*;
+pub use self::statistics::*;
 pub use self::unsigned::*;
+pub use self::value::*;
+
+#[macro_use]
+mod macros;
 
+mod api_trace;
+mod array_value;
+mod assignment;
 mod bit_vec_assignment;
+mod bit_vec_value;
 mod builder;
+mod bv;
+mod cfile;
+mod checked_result;
+mod config;
+mod dag;
+mod dot;
+mod engine;
+mod eval;
 mod generate_model;
+mod leak_check;
 mod model;
+mod model_counter;
+mod model_error;
+mod model_snapshot;
+mod models;
 mod node;
 mod node_ref;
+mod optimize;
+mod owned_node_ref;
+mod owned_sort_ref;
+pub mod portfolio;
+mod query_cache;
+mod replay;
+mod sat_solver;
+mod shared_solver;
+mod shift;
+mod smt2;
 mod solve_result;
 mod solver;
+mod solver_pool;
 mod sort;
 mod sort_ref;
+mod statistics;
+mod termination;
 mod unsigned;
+mod value;
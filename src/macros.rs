@@ -0,0 +1,149 @@
+use crate::{BitVecValue, NodeRef};
+
+/// Expression wrapper that operator implementations can be generated for.
+pub(crate) trait Operand<'a>: Sized {
+    /// Return the underlying node reference.
+    fn node(&self) -> &NodeRef<'a>;
+
+    /// Wrap a node reference.
+    fn from_node(node: NodeRef<'a>) -> Self;
+
+    /// Create a constant with the same width as `self` from an integer
+    /// literal, sign-extending it if `signed` is set.
+    ///
+    /// Panics if the literal does not fit in the width, read either as signed
+    /// or as unsigned.
+    fn literal(&self, value: u64, signed: bool) -> Self {
+        let node = self.node();
+        let width = node.width();
+
+        let value = if signed {
+            let value = value as i64;
+            let fits = width >= 64 || (value >= -(1 << (width - 1)) && value < 1 << width);
+            assert!(fits, "literal {} does not fit in {} bits", value, width);
+
+            BitVecValue::from_i64(width, value)
+        } else {
+            let fits = width >= 64 || value >> width == 0;
+            assert!(fits, "literal {} does not fit in {} bits", value, width);
+
+            BitVecValue::from_u64(width, value)
+        };
+
+        Self::from_node(value.to_node(node.solver))
+    }
+}
+
+/// Implement a binary operator and its compound assignment counterpart for all
+/// combinations of owned and borrowed operands, and for integer literals on the
+/// right-hand side.  Literals become constants of the left operand's width and
+/// panic if they do not fit in it.
+macro_rules! impl_binary_op {
+    (
+        $ty:ident, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident,
+        |$a:ident, $b:ident| $body:expr
+    ) => {
+        impl<'a> ops::$trait<&$ty<'a>> for &$ty<'a> {
+            type Output = $ty<'a>;
+
+            fn $method(self, other: &$ty<'a>) -> Self::Output {
+                let ($a, $b) = (self, other);
+                $body
+            }
+        }
+
+        impl<'a> ops::$trait<$ty<'a>> for &$ty<'a> {
+            type Output = $ty<'a>;
+
+            fn $method(self, other: $ty<'a>) -> Self::Output {
+                ops::$trait::$method(self, &other)
+            }
+        }
+
+        impl<'a> ops::$trait<&$ty<'a>> for $ty<'a> {
+            type Output = $ty<'a>;
+
+            fn $method(self, other: &$ty<'a>) -> Self::Output {
+                ops::$trait::$method(&self, other)
+            }
+        }
+
+        impl<'a> ops::$trait<$ty<'a>> for $ty<'a> {
+            type Output = $ty<'a>;
+
+            fn $method(self, other: $ty<'a>) -> Self::Output {
+                ops::$trait::$method(&self, &other)
+            }
+        }
+
+        impl<'a> ops::$assign_trait<&$ty<'a>> for $ty<'a> {
+            fn $assign_method(&mut self, other: &$ty<'a>) {
+                *self = ops::$trait::$method(&*self, other);
+            }
+        }
+
+        impl<'a> ops::$assign_trait<$ty<'a>> for $ty<'a> {
+            fn $assign_method(&mut self, other: $ty<'a>) {
+                *self = ops::$trait::$method(&*self, &other);
+            }
+        }
+
+        impl_binary_op!(
+            @literals $ty, $trait, $method, $assign_trait, $assign_method,
+            u8 false, u16 false, u32 false, u64 false,
+            i8 true, i16 true, i32 true, i64 true
+        );
+    };
+
+    (
+        @literals $ty:ident, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident,
+        $($int:ident $signed:expr),*
+    ) => {
+        $(
+            impl<'a> ops::$trait<$int> for &$ty<'a> {
+                type Output = $ty<'a>;
+
+                fn $method(self, other: $int) -> Self::Output {
+                    let other = $crate::macros::Operand::literal(self, other as u64, $signed);
+                    ops::$trait::$method(self, &other)
+                }
+            }
+
+            impl<'a> ops::$trait<$int> for $ty<'a> {
+                type Output = $ty<'a>;
+
+                fn $method(self, other: $int) -> Self::Output {
+                    ops::$trait::$method(&self, other)
+                }
+            }
+
+            impl<'a> ops::$assign_trait<$int> for $ty<'a> {
+                fn $assign_method(&mut self, other: $int) {
+                    *self = ops::$trait::$method(&*self, other);
+                }
+            }
+        )*
+    };
+}
+
+/// Implement a unary operator for owned and borrowed operands.
+macro_rules! impl_unary_op {
+    ($ty:ident, $trait:ident, $method:ident, |$a:ident| $body:expr) => {
+        impl<'a> ops::$trait for &$ty<'a> {
+            type Output = $ty<'a>;
+
+            fn $method(self) -> Self::Output {
+                let $a = self;
+                $body
+            }
+        }
+
+        impl<'a> ops::$trait for $ty<'a> {
+            type Output = $ty<'a>;
+
+            fn $method(self) -> Self::Output {
+                ops::$trait::$method(&self)
+            }
+        }
+    };
+}
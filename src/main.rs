@@ -1,4 +1,54 @@
+use std::io::BufReader;
+use std::{env, fs, process};
+
 fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("replay") => replay(&args[1..]),
+        Some(_) => {
+            eprintln!("usage: boolector [replay TRACE]");
+            process::exit(2);
+        },
+        None => demo(),
+    }
+}
+
+/// Re-execute an API trace recorded with `Builder::trace_api` or
+/// `BTORAPITRACE`.
+fn replay(args: &[String]) {
+    let path = match args {
+        [path] => path,
+        _ => {
+            eprintln!("usage: boolector replay TRACE");
+            process::exit(2);
+        },
+    };
+
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            process::exit(1);
+        },
+    };
+
+    match boolector::replay(BufReader::new(file)) {
+        Ok(summary) => {
+            println!("replayed {} calls", summary.calls);
+
+            for (index, sat) in summary.solve_results.iter().enumerate() {
+                println!("sat call {}: {}", index + 1, if *sat { "sat" } else { "unsat" });
+            }
+        },
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            process::exit(1);
+        },
+    }
+}
+
+fn demo() {
     let solver = boolector::Builder::new()
         .generate_model(boolector::GenerateModel::Asserted)
         .finish();
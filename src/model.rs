@@ -1,6 +1,10 @@
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::{ptr, slice};
+
 use boolector_sys as ffi;
 
-use crate::{BitVecAssignment, NodeRef, Solver};
+use crate::{ArrayValue, BitVecAssignment, BitVecValue, NodeRef, SnapshotValue, Solver, Value};
 
 /// Model generated for a satisfiable formula.
 pub struct Model<'a> {
@@ -9,12 +13,12 @@ pub struct Model<'a> {
 
 impl<'a> Model<'a> {
     /// Return parent solver instance.
-    pub fn solver(&self) -> &Solver {
+    pub fn solver(&self) -> &'a Solver {
         self.solver
     }
 
     /// Return assignment for a bit vector expression.
-    pub fn bit_vec(&'a self, expr: &'a NodeRef<'a>) -> BitVecAssignment<'a> {
+    pub fn bit_vec(&self, expr: &NodeRef<'_>) -> BitVecAssignment<'a> {
         assert_eq!(self.solver().btor_ptr(), expr.solver().btor_ptr());
 
         BitVecAssignment {
@@ -27,4 +31,200 @@ impl<'a> Model<'a> {
             },
         }
     }
+
+    /// Return the value of a bit vector, boolean or array expression.
+    ///
+    /// Booleans are 1-bit vectors.  Arbitrary bits, and array elements the
+    /// model does not fix, are reported as zeros.
+    ///
+    /// Expressions created after the `solve` call are evaluated in Rust, as
+    /// by [eval](fn.eval.html), over the values of the nodes they are built
+    /// from; this requires them to be recorded (see
+    /// [Builder::record_dag](struct.Builder.html#method.record_dag)).
+    /// Variables created after the call are unconstrained and read as zeros.
+    ///
+    /// Panics if `expr` is an uninterpreted function, or if it was created
+    /// after the `solve` call from nodes that are not recorded.
+    ///
+    /// ```
+    /// use boolector::{BitVecValue, Builder, GenerateModel, Node, Sort, Unsigned};
+    ///
+    /// let solver = Builder::new().generate_model(GenerateModel::Asserted).record_dag(true).finish();
+    /// let b8 = solver.sort(Sort::BitVec(8));
+    /// let x = b8.var(Some("x"));
+    /// let ten = BitVecValue::from_u64(8, 10).to_node(&solver);
+    /// solver.assert(&solver.node(Node::Eq(&(&x + 2), &ten)));
+    ///
+    /// if let boolector::SolveResult::Sat(Some(model)) = solver.solve() {
+    ///     let doubled = &x * 2;
+    ///     assert_eq!(model.eval(&doubled).as_bit_vec().unwrap().to_u64(), Some(16));
+    ///
+    ///     let small = Unsigned(x.clone()).lt(&Unsigned(ten.clone()));
+    ///     assert_eq!(model.eval(&small).as_bit_vec().unwrap().to_bool(), Some(true));
+    /// }
+    /// ```
+    pub fn eval(&self, expr: &NodeRef<'_>) -> Value {
+        assert_eq!(self.solver().btor_ptr(), expr.solver().btor_ptr());
+
+        if self.solver.existed_at_solve(expr) {
+            return self.value(expr);
+        }
+
+        crate::eval::eval_with(expr, |node| {
+            if self.solver.existed_at_solve(node) {
+                return self.value(node);
+            }
+
+            let btor_ptr = self.solver.btor_ptr();
+
+            unsafe {
+                if ffi::boolector_is_array_var(btor_ptr, node.node_ptr()) {
+                    let index_width = ffi::boolector_get_index_width(btor_ptr, node.node_ptr());
+                    return Value::Array(ArrayValue::new(index_width, BitVecValue::zero(node.width())));
+                }
+
+                if ffi::boolector_is_var(btor_ptr, node.node_ptr()) {
+                    return Value::BitVec(BitVecValue::zero(node.width()));
+                }
+            }
+
+            panic!("cannot evaluate node {}: it was created after solving and is not recorded", node.id());
+        })
+    }
+
+    /// Return the value the model gives to a node that existed when solving.
+    fn value(&self, expr: &NodeRef<'_>) -> Value {
+        let btor_ptr = self.solver.btor_ptr();
+
+        let (is_array, is_fun) = unsafe {
+            (ffi::boolector_is_array(btor_ptr, expr.node_ptr()), ffi::boolector_is_fun(btor_ptr, expr.node_ptr()))
+        };
+
+        if is_array {
+            let index_width = unsafe { ffi::boolector_get_index_width(btor_ptr, expr.node_ptr()) };
+            let mut array = ArrayValue::new(index_width, BitVecValue::zero(expr.width()));
+
+            for (index, element) in self.array(expr) {
+                array.insert(index, element);
+            }
+
+            Value::Array(array)
+        } else {
+            assert!(!is_fun, "cannot evaluate an uninterpreted function");
+            Value::BitVec(BitVecValue::from_bits(self.bit_vec(expr).to_str()))
+        }
+    }
+
+    /// Iterate over every variable of the solver instance with its value, in
+    /// order of creation, see [Solver::vars](struct.Solver.html#method.vars).
+    ///
+    /// ```
+    /// use boolector::{Builder, GenerateModel, Node, SnapshotValue, Sort};
+    ///
+    /// let solver = Builder::new().generate_model(GenerateModel::Asserted).finish();
+    /// let b8 = solver.sort(Sort::BitVec(8));
+    /// let x = b8.var(Some("x"));
+    /// let y = b8.var(Some("y"));
+    /// solver.assert(&solver.node(Node::Eq(&(&x + &y), &(&x * 2))));
+    ///
+    /// if let boolector::SolveResult::Sat(Some(model)) = solver.solve() {
+    ///     for (var, value) in model.iter() {
+    ///         if let SnapshotValue::BitVec(value) = value {
+    ///             println!("{} = {}", var.symbol().unwrap_or_default(), value);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (NodeRef<'a>, SnapshotValue)> + 'a {
+        let model = Model { solver: self.solver };
+
+        self.solver
+            .vars()
+            .into_iter()
+            .map(move |var| {
+                let value = model.snapshot_value(&var);
+                (var, value)
+            })
+    }
+
+    /// Return the elements that the model fixes for an array expression, as
+    /// pairs of index and element; elements at other indexes are arbitrary.
+    ///
+    /// Arbitrary bits are reported as zeros.
+    pub fn array(&self, expr: &NodeRef<'_>) -> Vec<(BitVecValue, BitVecValue)> {
+        assert_eq!(self.solver().btor_ptr(), expr.solver().btor_ptr());
+
+        let btor_ptr = self.solver.btor_ptr();
+
+        unsafe {
+            assert!(ffi::boolector_is_array(btor_ptr, expr.node_ptr()), "expression is not an array");
+
+            let mut indices = ptr::null_mut();
+            let mut values = ptr::null_mut();
+            let mut size = 0;
+
+            ffi::boolector_array_assignment(btor_ptr, expr.node_ptr(), &mut indices, &mut values, &mut size);
+
+            let entries = read_table(indices, values, size)
+                .map(|(index, value)| (BitVecValue::from_bits(index), BitVecValue::from_bits(value)))
+                .collect();
+
+            if size != 0 {
+                ffi::boolector_free_array_assignment(btor_ptr, indices, values, size);
+            }
+
+            entries
+        }
+    }
+
+    /// Return the function table that the model fixes for an uninterpreted
+    /// function, as pairs of arguments and result; results for other
+    /// arguments are arbitrary.
+    ///
+    /// Arbitrary bits are reported as zeros.
+    pub fn uf(&self, expr: &NodeRef<'_>) -> Vec<(Vec<BitVecValue>, BitVecValue)> {
+        assert_eq!(self.solver().btor_ptr(), expr.solver().btor_ptr());
+
+        let btor_ptr = self.solver.btor_ptr();
+
+        unsafe {
+            assert!(ffi::boolector_is_uf(btor_ptr, expr.node_ptr()), "expression is not an uninterpreted function");
+
+            let mut args = ptr::null_mut();
+            let mut values = ptr::null_mut();
+            let mut size = 0;
+
+            ffi::boolector_uf_assignment(btor_ptr, expr.node_ptr(), &mut args, &mut values, &mut size);
+
+            // Arguments are separated by spaces.
+            let entries = read_table(args, values, size)
+                .map(|(args, value)| {
+                    let args = args.split(' ').map(BitVecValue::from_bits).collect();
+                    (args, BitVecValue::from_bits(value))
+                })
+                .collect();
+
+            if size != 0 {
+                ffi::boolector_free_uf_assignment(btor_ptr, args, values, size);
+            }
+
+            entries
+        }
+    }
+}
+
+/// Iterate over the rows of a table returned by `boolector_array_assignment`
+/// or `boolector_uf_assignment`.
+unsafe fn read_table<'t>(keys: *mut *mut c_char, values: *mut *mut c_char, size: u32) -> impl Iterator<Item = (&'t str, &'t str)> {
+    let (keys, values): (&[*mut c_char], &[*mut c_char]) = if size == 0 {
+        (&[], &[])
+    } else {
+        (slice::from_raw_parts(keys, size as usize), slice::from_raw_parts(values, size as usize))
+    };
+
+    keys.iter().zip(values).map(|(&key, &value)| {
+        let key = CStr::from_ptr(key).to_str().expect("unexpected character in model");
+        let value = CStr::from_ptr(value).to_str().expect("unexpected character in model");
+        (key, value)
+    })
 }
@@ -0,0 +1,191 @@
+use crate::{BitVecValue, Node, NodeRef, Solver};
+
+/// Approximate model counter in the style of ApproxMC.
+///
+/// The counter splits the projected solution space into cells with random XOR
+/// constraints and counts the models of one cell, up to a threshold.  With
+/// probability at least `1 - delta`, the estimate lies within a factor of
+/// `1 + epsilon` of the true count.
+///
+/// ```
+/// use boolector::{BitVecValue, Builder, GenerateModel, ModelCounter, Node, Sort};
+///
+/// let solver = Builder::new()
+///     .incremental(true)
+///     .generate_model(GenerateModel::Asserted)
+///     .finish();
+///
+/// let b6 = solver.sort(Sort::BitVec(6));
+/// let x = b6.var(Some("x"));
+///
+/// // 40 values of x satisfy x < 40.
+/// let bound = BitVecValue::from_u64(6, 40).to_node(&solver);
+/// solver.assert(&solver.node(Node::ULt(&x, &bound)));
+///
+/// // A loose tolerance keeps the cells, and so the enumerations, small.
+/// let estimate = ModelCounter::new()
+///     .epsilon(4.0)
+///     .delta(0.8)
+///     .seed(42)
+///     .count(&solver, &[&x])
+///     .unwrap();
+///
+/// assert!(estimate >= 40.0 / 5.0 && estimate <= 40.0 * 5.0);
+/// ```
+pub struct ModelCounter {
+    epsilon: f64,
+    delta: f64,
+    seed: u64,
+}
+
+impl ModelCounter {
+    /// Create a counter with tolerance 0.8, confidence 0.8 and seed 0.
+    pub fn new() -> Self {
+        ModelCounter {
+            epsilon: 0.8,
+            delta: 0.2,
+            seed: 0,
+        }
+    }
+
+    /// Set the tolerance.
+    ///
+    /// Panics if `epsilon` is not positive.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        assert!(epsilon > 0.0, "tolerance must be positive");
+
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Set the probability that the estimate lies outside the tolerance.
+    ///
+    /// Panics if `delta` is not strictly between 0 and 1.
+    pub fn delta(mut self, delta: f64) -> Self {
+        assert!(delta > 0.0 && delta < 1.0, "confidence parameter must lie between 0 and 1");
+
+        self.delta = delta;
+        self
+    }
+
+    /// Set the seed of the random XOR constraints; equal seeds give equal
+    /// estimates for the same formula.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Estimate the number of distinct values of `vars` over all models.
+    ///
+    /// Counts below the cell threshold are exact.  Returns `None` if a `solve`
+    /// call of the exact count is terminated, or if no iteration produces an
+    /// estimate.  Requires incremental solving and model generation;
+    /// assertions are left unchanged.
+    pub fn count<'a>(&self, solver: &'a Solver, vars: &[&NodeRef<'a>]) -> Option<f64> {
+        let threshold = self.threshold();
+        let exact = count_models(solver, vars, threshold)?;
+
+        if exact < threshold {
+            return Some(exact as f64);
+        }
+
+        let mut rng = SplitMix64(self.seed);
+        let mut estimates: Vec<f64> = (0..self.iterations())
+            .filter_map(|_| estimate(solver, vars, threshold, &mut rng))
+            .collect();
+
+        if estimates.is_empty() {
+            return None;
+        }
+
+        estimates.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Some(estimates[estimates.len() / 2])
+    }
+
+    /// Maximum number of models counted in one cell.
+    fn threshold(&self) -> usize {
+        let epsilon = self.epsilon;
+        let factor = 1.0 + 1.0 / epsilon;
+
+        (1.0 + 9.84 * (1.0 + epsilon / (1.0 + epsilon)) * factor * factor).ceil() as usize
+    }
+
+    /// Number of independent estimates whose median is returned.
+    fn iterations(&self) -> usize {
+        (17.0 * (3.0 / self.delta).log2()).ceil() as usize
+    }
+}
+
+impl Default for ModelCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Add XOR constraints one at a time until a cell has fewer than `threshold`
+/// models, then scale its count by the number of cells.  Gives up if
+/// enumeration is interrupted.
+fn estimate<'a>(solver: &'a Solver, vars: &[&NodeRef<'a>], threshold: usize, rng: &mut SplitMix64) -> Option<f64> {
+    let bits: u32 = vars.iter().map(|var| var.width()).sum();
+
+    solver.push(1);
+
+    let result = (1..=bits).find_map(|cells_log2| {
+        solver.assert(&random_xor(solver, vars, rng));
+
+        match count_models(solver, vars, threshold) {
+            Some(count) if count < threshold => Some(Some(count as f64 * 2f64.powi(cells_log2 as i32))),
+            Some(_) => None,
+            None => Some(None),
+        }
+    });
+
+    solver.pop(1);
+    result.flatten()
+}
+
+/// Count models up to `limit`, or return `None` if enumeration is interrupted.
+fn count_models<'a>(solver: &'a Solver, vars: &[&NodeRef<'a>], limit: usize) -> Option<usize> {
+    let mut models = solver.models_over(vars, limit);
+    let count = models.by_ref().count();
+
+    if models.interrupted() { None } else { Some(count) }
+}
+
+/// Build the constraint that the parity of a random subset of the bits of
+/// `vars` equals a random bit.
+fn random_xor<'a>(solver: &'a Solver, vars: &[&NodeRef<'a>], rng: &mut SplitMix64) -> NodeRef<'a> {
+    let parity = vars
+        .iter()
+        .filter_map(|var| {
+            let chosen = (0..var.width())
+                .filter(|_| rng.next_bit())
+                .map(|bit| Node::Slice(var, bit, bit).into_ref(solver))
+                .reduce(|a, b| Node::Concat(&a, &b).into_ref(solver))?;
+
+            Some(Node::RedXor(&chosen).into_ref(solver))
+        })
+        .reduce(|a, b| Node::Xor(&a, &b).into_ref(solver))
+        .unwrap_or_else(|| BitVecValue::from_bool(false).to_node(solver));
+
+    let target = BitVecValue::from_bool(rng.next_bit()).to_node(solver);
+    Node::Eq(&parity, &target).into_ref(solver)
+}
+
+/// Small deterministic generator, good enough for choosing hash functions.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_bit(&mut self) -> bool {
+        self.next() >> 63 == 1
+    }
+}
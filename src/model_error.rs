@@ -0,0 +1,27 @@
+use std::error::Error;
+use std::fmt;
+
+/// Asserted expression that does not hold under a generated model, see
+/// [Solver::solve_checked](struct.Solver.html#method.solve_checked).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModelError {
+    /// Position of the assertion among all assertions, starting from zero.
+    pub index: usize,
+
+    /// Node id of the asserted expression.
+    pub id: i32,
+
+    /// Symbol of the asserted expression, if any.
+    pub symbol: Option<String>,
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.symbol {
+            Some(symbol) => write!(f, "model violates assertion {}: {} (node {})", self.index, symbol, self.id),
+            None => write!(f, "model violates assertion {} (node {})", self.index, self.id),
+        }
+    }
+}
+
+impl Error for ModelError {}
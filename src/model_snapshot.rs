@@ -0,0 +1,204 @@
+use std::collections::btree_map::{self, BTreeMap};
+
+use boolector_sys as ffi;
+
+use crate::{BitVecValue, Model, Node, NodeRef, Solver, Sort, SortRef};
+
+/// Owned copy of the values a model gives to named variables.
+///
+/// A snapshot does not borrow its solver instance, so it can be kept after
+/// the next `solve` call, and with the `serde` feature it can be serialized.
+/// [assert_into](#method.assert_into) recreates the variables in another
+/// instance and asserts their values.
+///
+/// ```
+/// use boolector::{Builder, GenerateModel, ModelSnapshot, Sort};
+///
+/// let solver = Builder::new().generate_model(GenerateModel::Asserted).finish();
+/// let b8 = solver.sort(Sort::BitVec(8));
+/// let x = b8.var(Some("x"));
+/// solver.assert(&boolector::Unsigned(&x * 3).lt(&boolector::Unsigned(x.clone())));
+///
+/// let snapshot = match solver.solve() {
+///     boolector::SolveResult::Sat(Some(model)) => model.snapshot(&[&x]),
+///     _ => panic!("expected a model"),
+/// };
+///
+/// # #[cfg(feature = "serde")]
+/// # let snapshot: ModelSnapshot = {
+/// let json = serde_json::to_string(&snapshot).unwrap();
+/// serde_json::from_str(&json).unwrap()
+/// # };
+///
+/// let replay = Builder::new().finish();
+/// let vars = snapshot.assert_into(&replay);
+/// replay.assert(&boolector::Unsigned(&vars["x"] * 3).lt(&boolector::Unsigned(vars["x"].clone())));
+/// assert!(replay.solve().is_sat());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelSnapshot {
+    values: BTreeMap<String, SnapshotValue>,
+}
+
+/// Sort and value of one variable in a [ModelSnapshot](struct.ModelSnapshot.html).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapshotValue {
+    /// Bit vector value.
+    BitVec(BitVecValue),
+
+    /// Array with the elements the model fixes, as pairs of index and
+    /// element.
+    Array {
+        index_width: u32,
+        element_width: u32,
+        entries: Vec<(BitVecValue, BitVecValue)>,
+    },
+
+    /// Uninterpreted function with the results the model fixes, as pairs of
+    /// arguments and result.
+    Fun {
+        domain_widths: Vec<u32>,
+        codomain_width: u32,
+        entries: Vec<(Vec<BitVecValue>, BitVecValue)>,
+    },
+}
+
+impl ModelSnapshot {
+    /// Create an empty snapshot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the value of the variable with the given symbol.
+    pub fn get(&self, symbol: &str) -> Option<&SnapshotValue> {
+        self.values.get(symbol)
+    }
+
+    /// Set the value of the variable with the given symbol.
+    pub fn insert(&mut self, symbol: String, value: SnapshotValue) {
+        self.values.insert(symbol, value);
+    }
+
+    /// Iterate over symbols and values, ordered by symbol.
+    pub fn iter(&self) -> btree_map::Iter<'_, String, SnapshotValue> {
+        self.values.iter()
+    }
+
+    /// Create a variable for every symbol in `solver`, assert the values in
+    /// this snapshot, and return the variables by symbol.
+    ///
+    /// Array elements and function results that the snapshot does not fix are
+    /// left unconstrained.
+    ///
+    /// Panics if the widths of a value are inconsistent.
+    pub fn assert_into<'a>(&self, solver: &'a Solver) -> BTreeMap<String, NodeRef<'a>> {
+        self.values
+            .iter()
+            .map(|(symbol, value)| (symbol.clone(), value.assert_into(solver, symbol)))
+            .collect()
+    }
+}
+
+impl SnapshotValue {
+    fn assert_into<'a>(&self, solver: &'a Solver, symbol: &str) -> NodeRef<'a> {
+        let btor_ptr = solver.btor_ptr();
+        let fixed = |expr: &NodeRef<'a>, value: &BitVecValue| {
+            assert_eq!(expr.width(), value.width(), "width of value for `{}` does not match", symbol);
+            solver.assert(&Node::Eq(expr, &value.to_node(solver)).into_ref(solver));
+        };
+
+        match self {
+            SnapshotValue::BitVec(value) => {
+                let var = solver.sort(Sort::BitVec(value.width())).var(Some(symbol));
+                fixed(&var, value);
+                var
+            },
+            SnapshotValue::Array { index_width, element_width, entries } => {
+                let index_sort = solver.sort(Sort::BitVec(*index_width));
+                let element_sort = solver.sort(Sort::BitVec(*element_width));
+                let array = solver.sort(Sort::Array(&index_sort, &element_sort)).var(Some(symbol));
+
+                for (index, element) in entries {
+                    assert_eq!(index.width(), *index_width, "index width for `{}` does not match", symbol);
+
+                    let element_ref = unsafe {
+                        NodeRef::wrap(solver, ffi::boolector_read(btor_ptr, array.node_ptr(), index.to_node(solver).node_ptr()))
+                    };
+
+                    fixed(&element_ref, element);
+                }
+
+                array
+            },
+            SnapshotValue::Fun { domain_widths, codomain_width, entries } => {
+                let domain: Vec<SortRef<'a>> = domain_widths.iter().map(|&width| solver.sort(Sort::BitVec(width))).collect();
+                let domain_refs: Vec<&SortRef<'a>> = domain.iter().collect();
+                let codomain = solver.sort(Sort::BitVec(*codomain_width));
+                let fun = solver.sort(Sort::Fun(&domain_refs, &codomain)).var(Some(symbol));
+
+                for (args, result) in entries {
+                    assert!(
+                        args.len() == domain_widths.len() && args.iter().zip(domain_widths).all(|(arg, &width)| arg.width() == width),
+                        "argument widths for `{}` do not match",
+                        symbol,
+                    );
+
+                    let args: Vec<NodeRef<'a>> = args.iter().map(|arg| arg.to_node(solver)).collect();
+                    let mut arg_ptrs: Vec<_> = args.iter().map(NodeRef::node_ptr).collect();
+
+                    let result_ref = unsafe {
+                        NodeRef::wrap(solver, ffi::boolector_apply(btor_ptr, arg_ptrs.as_mut_ptr(), arg_ptrs.len() as u32, fun.node_ptr()))
+                    };
+
+                    fixed(&result_ref, result);
+                }
+
+                fun
+            },
+        }
+    }
+}
+
+impl<'a> Model<'a> {
+    /// Copy the values of the given variables into a snapshot, keyed by their
+    /// symbols.
+    ///
+    /// Panics if a variable has no symbol.
+    pub fn snapshot(&self, vars: &[&NodeRef<'_>]) -> ModelSnapshot {
+        let mut snapshot = ModelSnapshot::new();
+
+        for var in vars {
+            let symbol = var.symbol().expect("variable has no symbol");
+
+            snapshot.insert(symbol, self.snapshot_value(var));
+        }
+
+        snapshot
+    }
+
+    pub(crate) fn snapshot_value(&self, var: &NodeRef<'_>) -> SnapshotValue {
+        let btor_ptr = self.solver.btor_ptr();
+
+        let (is_array, is_fun) = unsafe {
+            (ffi::boolector_is_array(btor_ptr, var.node_ptr()), ffi::boolector_is_fun(btor_ptr, var.node_ptr()))
+        };
+
+        if is_array {
+            SnapshotValue::Array {
+                index_width: unsafe { ffi::boolector_get_index_width(btor_ptr, var.node_ptr()) },
+                element_width: var.width(),
+                entries: self.array(var),
+            }
+        } else if is_fun {
+            SnapshotValue::Fun {
+                domain_widths: var.sort().domain().iter().map(SortRef::width).collect(),
+                codomain_width: var.width(),
+                entries: self.uf(var),
+            }
+        } else {
+            SnapshotValue::BitVec(BitVecValue::from_bits(self.bit_vec(var).to_str()))
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use boolector_sys as ffi;
+
+use crate::{BitVecValue, Node, NodeRef, SolveResult, Solver};
+
+/// Iterator over distinct models projected onto chosen expressions, see
+/// [Solver::models_over](struct.Solver.html#method.models_over).
+pub struct Models<'a> {
+    solver: &'a Solver,
+    exprs: Vec<NodeRef<'a>>,
+    remaining: usize,
+    interrupted: bool,
+    // Number of open scopes including the one holding the blocking assertions.
+    depth: u32,
+}
+
+impl<'a> Iterator for Models<'a> {
+    type Item = Vec<BitVecValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let values: Vec<_> = match self.solver.solve() {
+            SolveResult::Sat(Some(model)) => self.exprs
+                .iter()
+                .map(|expr| BitVecValue::from_bits(model.bit_vec(expr).to_str()))
+                .collect(),
+            SolveResult::Sat(None) => panic!("model generation is not enabled"),
+            SolveResult::Unsat => {
+                self.remaining = 0;
+                return None;
+            },
+            SolveResult::Unknown => {
+                self.remaining = 0;
+                self.interrupted = true;
+                return None;
+            },
+        };
+
+        self.remaining -= 1;
+
+        if self.exprs.is_empty() {
+            self.remaining = 0;
+            return Some(values);
+        }
+
+        // Block this projection: at least one expression must differ.
+        let blocking = self.exprs
+            .iter()
+            .zip(&values)
+            .map(|(expr, value)| Node::Ne(expr, &value.to_node(self.solver)).into_ref(self.solver))
+            .reduce(|a, b| Node::Or(&a, &b).into_ref(self.solver))
+            .unwrap();
+
+        self.solver.assert(&blocking);
+        Some(values)
+    }
+}
+
+impl<'a> Models<'a> {
+    /// Return whether enumeration stopped because a `solve` call was
+    /// terminated, so that more models may exist.
+    pub fn interrupted(&self) -> bool {
+        self.interrupted
+    }
+}
+
+impl<'a> Drop for Models<'a> {
+    fn drop(&mut self) {
+        let open = self.solver.scopes();
+
+        // The caller may have closed the scope already, or opened more inside.
+        if open >= self.depth {
+            self.solver.pop(open - self.depth + 1);
+        }
+    }
+}
+
+impl Solver {
+    /// Enumerate distinct values of `exprs` over all models, at most `limit`
+    /// of them.
+    ///
+    /// Each step solves the formula, yields the values of `exprs`, and blocks
+    /// that combination of values.  Enumeration stops when the formula becomes
+    /// unsatisfiable, or when a `solve` call is terminated, see
+    /// [Models::interrupted](struct.Models.html#method.interrupted).  Blocking assertions live in a scope that is closed when
+    /// the iterator is dropped, together with any scope opened inside it,
+    /// leaving the instance as it was.
+    ///
+    /// Requires incremental solving and model generation.  Bits that a model
+    /// leaves arbitrary are reported as zeros.
+    ///
+    /// ```
+    /// use boolector::{BitVecValue, Builder, GenerateModel, Node, Sort};
+    ///
+    /// let solver = Builder::new()
+    ///     .incremental(true)
+    ///     .generate_model(GenerateModel::Asserted)
+    ///     .finish();
+    ///
+    /// let b4 = solver.sort(Sort::BitVec(4));
+    /// let x = b4.var(Some("x"));
+    /// let y = b4.var(Some("y"));
+    ///
+    /// // y == x * 3 for x < 4: four possible values of y.
+    /// let four = BitVecValue::from_u64(4, 4).to_node(&solver);
+    /// solver.assert(&solver.node(Node::ULt(&x, &four)));
+    /// solver.assert(&solver.node(Node::Eq(&y, &(&x * 3))));
+    ///
+    /// let mut values: Vec<_> = solver
+    ///     .models_over(&[&y], 100)
+    ///     .map(|values| values[0].to_u64().unwrap())
+    ///     .collect();
+    ///
+    /// values.sort();
+    /// assert_eq!(values, vec![0, 3, 6, 9]);
+    /// ```
+    pub fn models_over<'a>(&'a self, exprs: &[&NodeRef<'a>], limit: usize) -> Models<'a> {
+        let incremental = unsafe {
+            ffi::boolector_get_opt(self.btor_ptr(), ffi::BtorOption_BTOR_OPT_INCREMENTAL)
+        };
+
+        assert!(incremental != 0, "incremental solving is not enabled");
+
+        for expr in exprs {
+            assert_eq!(self.btor_ptr(), expr.solver().btor_ptr());
+        }
+
+        self.push(1);
+
+        Models {
+            solver: self,
+            exprs: exprs.iter().map(|&expr| expr.clone()).collect(),
+            remaining: limit,
+            interrupted: false,
+            depth: self.scopes(),
+        }
+    }
+}
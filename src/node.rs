@@ -6,160 +6,188 @@ use crate::{NodeRef, Solver};
 ///
 /// For references to node instances, see [NodeRef](struct.NodeRef.html).
 ///
+/// Operands are only borrowed while the node is being created, so temporaries
+/// can be passed directly:
+///
+/// ```
+/// use boolector::{Builder, Node, Sort};
+///
+/// let solver = Builder::new().finish();
+/// let b8 = solver.sort(Sort::BitVec(8));
+/// let x = b8.var(Some("x"));
+///
+/// let twice = solver.node(Node::Add(&x, &x));
+/// let overflow = solver.node(Node::ULt(&solver.node(Node::Add(&twice, &x)), &x));
+/// solver.assert(&solver.node(Node::Not(&overflow)));
+/// ```
+///
 /// # Shifts and rotates
 ///
 /// For shift and rotate operations Boolector requires that the first argument
 /// has length `n` that is power of two and the second argument has length equal
-/// to `log2(n)`.
-pub enum Node<'a> {
+/// to `log2(n)`.  Methods such as
+/// [NodeRef::shift_left](struct.NodeRef.html#method.shift_left) and
+/// [NodeRef::rotate_left](struct.NodeRef.html#method.rotate_left) accept
+/// operands of any length.
+pub enum Node<'a, 'b> {
     /// Integer addition for bit vectors.
-    Add(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Add(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Bitwise AND for bit vectors.
-    And(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    And(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Concatenation for bit vectors.
-    Concat(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Concat(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// If-then-else conditional for bit vectors or arrays, the condition must
     /// be a boolean.
-    Cond(&'a NodeRef<'a>, &'a NodeRef<'a>, &'a NodeRef<'a>),
+    Cond(&'b NodeRef<'a>, &'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Integer decrement for bit vectors.
-    Dec(&'a NodeRef<'a>),
+    Dec(&'b NodeRef<'a>),
 
     /// Equality for bit vectors or arrays.
-    Eq(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Eq(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Equivalence for booleans.
-    Iff(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Iff(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Integer increment for bit vectors.
-    Inc(&'a NodeRef<'a>),
+    Inc(&'b NodeRef<'a>),
 
     /// Integer truncating multiplication for bit vectors.
-    Mul(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Mul(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Bitwise NAND for bit vectors.
-    NAnd(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    NAnd(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Inequality for bit vectors or arrays.
-    Ne(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Ne(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Two's complement (signed integer) negation for bit vectors.
-    Neg(&'a NodeRef<'a>),
+    Neg(&'b NodeRef<'a>),
 
     /// Bitwise NOR for bit vectors.
-    NOr(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    NOr(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// One's complement negation for bit vectors.
-    Not(&'a NodeRef<'a>),
+    Not(&'b NodeRef<'a>),
 
     /// Bitwise OR for bit vectors.
-    Or(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Or(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// AND-reduction for bit vectors.
-    RedAnd(&'a NodeRef<'a>),
+    RedAnd(&'b NodeRef<'a>),
 
     /// OR-reduction for bit vectors.
-    RedOr(&'a NodeRef<'a>),
+    RedOr(&'b NodeRef<'a>),
 
     /// XOR-reduction for bit vectors.
-    RedXor(&'a NodeRef<'a>),
+    RedXor(&'b NodeRef<'a>),
 
     /// Rotate left for bit vectors, see note above about shifts and rotates.
-    Rol(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Rol(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Rotate right for bit vectors, see note above about shifts and rotates.
-    Ror(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Ror(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer addition overflow flag for bit vectors.
-    SAddO(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SAddO(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer division for bit vectors.
-    SDiv(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SDiv(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer division overflow flag for bit vectors.
-    SDivO(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SDivO(&'b NodeRef<'a>, &'b NodeRef<'a>),
+
+    /// Sign extension for bit vectors by the given number of bits.
+    SExt(&'b NodeRef<'a>, u32),
 
     /// Signed integer greater-than comparison for bit vectors.
-    SGt(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SGt(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer greater-than-or-equal comparison for bit vectors.
-    SGte(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SGte(&'b NodeRef<'a>, &'b NodeRef<'a>),
+
+    /// Slice for bit vectors, from the given upper bit down to the given lower
+    /// bit (both inclusive).
+    Slice(&'b NodeRef<'a>, u32, u32),
 
     /// Shift left for bit vectors, see note above about shifts and rotates.
-    Sll(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Sll(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer less-than comparison for bit vectors.
-    SLt(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SLt(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer less-than-or-equal comparison for bit vectors.
-    SLte(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SLte(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer modulo (sign matches the divisor sign) for bit vectors.
-    SMod(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SMod(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer multiplication overflow flag for bit vectors.
-    SMulO(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SMulO(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Arithmetic shift right for bit vectors, see note above about shifts and
     /// rotates.
-    Sra(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Sra(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer remainder for bit vectors.
-    SRem(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SRem(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Logical shift right for bit vectors, see note above about shifts and
     /// rotates.
-    Srl(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Srl(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Signed integer subtraction overflow flag for bit vectors.
-    SSubO(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    SSubO(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Integer subtraction for bit vectors.
-    Sub(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Sub(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Unsigned integer addition overflow flag for bit vectors.
-    UAddO(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    UAddO(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Unsigned integer division for bit vectors, returns -1 for division by
     /// zero.
-    UDiv(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    UDiv(&'b NodeRef<'a>, &'b NodeRef<'a>),
+
+    /// Zero extension for bit vectors by the given number of bits.
+    UExt(&'b NodeRef<'a>, u32),
 
     /// Unsigned integer greater-than comparison for bit vectors.
-    UGt(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    UGt(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Unsigned integer greater-than-or-equal comparison for bit vectors.
-    UGte(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    UGte(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Unsigned integer less-than comparison for bit vectors.
-    ULt(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    ULt(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Unsigned integer less-than-or-equal comparison for bit vectors.
-    ULte(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    ULte(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Unsigned integer multiplication overflow flag for bit vectors.
-    UMulO(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    UMulO(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
-    /// Unsigned integer remainder for bit vectors, returns 0 for division by
-    /// zero.
-    URem(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    /// Unsigned integer remainder for bit vectors, returns the dividend for
+    /// division by zero.
+    URem(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Unsigned integer subtraction overflow flag for bit vectors.
-    USubO(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    USubO(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Bitwise XNOR for bit vectors.
-    XNOr(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    XNOr(&'b NodeRef<'a>, &'b NodeRef<'a>),
 
     /// Bitwise XOR for bit vectors.
-    Xor(&'a NodeRef<'a>, &'a NodeRef<'a>),
+    Xor(&'b NodeRef<'a>, &'b NodeRef<'a>),
 }
 
-impl<'a> Node<'a> {
+impl<'a, 'b> Node<'a, 'b> {
     /// Create a node reference.
     pub fn into_ref(self, solver: &'a Solver) -> NodeRef<'a> {
-        match self {
+        let node = match self {
             Node::Add(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_add),
             Node::And(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_and),
             Node::Concat(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_concat),
@@ -183,8 +211,10 @@ impl<'a> Node<'a> {
             Node::SAddO(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_saddo),
             Node::SDiv(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_sdiv),
             Node::SDivO(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_sdivo),
+            Node::SExt(a, width) => Self::into_ref_ffi_ext(solver, a, width, ffi::boolector_sext),
             Node::SGt(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_sgt),
             Node::SGte(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_sgte),
+            Node::Slice(a, upper, lower) => Self::into_ref_ffi_slice(solver, a, upper, lower),
             Node::Sll(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_sll),
             Node::SLt(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_slt),
             Node::SLte(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_slte),
@@ -197,6 +227,7 @@ impl<'a> Node<'a> {
             Node::Sub(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_sub),
             Node::UAddO(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_uaddo),
             Node::UDiv(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_udiv),
+            Node::UExt(a, width) => Self::into_ref_ffi_ext(solver, a, width, ffi::boolector_uext),
             Node::UGt(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_ugt),
             Node::UGte(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_ugte),
             Node::ULt(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_ult),
@@ -206,42 +237,67 @@ impl<'a> Node<'a> {
             Node::USubO(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_usubo),
             Node::XNOr(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_xnor),
             Node::Xor(a, b) => Self::into_ref_ffi_2(solver, a, b, ffi::boolector_xor),
-        }
+        };
+
+        solver.record_node(&node, &self);
+        node
     }
 }
 
-type NodeFn1 = unsafe extern fn(*mut ffi::Btor, *mut ffi::BoolectorNode) -> *mut ffi::BoolectorNode;
-type NodeFn2 = unsafe extern fn(*mut ffi::Btor, *mut ffi::BoolectorNode, *mut ffi::BoolectorNode) -> *mut ffi::BoolectorNode;
-type NodeFn3 = unsafe extern fn(*mut ffi::Btor, *mut ffi::BoolectorNode, *mut ffi::BoolectorNode, *mut ffi::BoolectorNode) -> *mut ffi::BoolectorNode;
+pub(crate) type NodeFn1 = unsafe extern "C" fn(*mut ffi::Btor, *mut ffi::BoolectorNode) -> *mut ffi::BoolectorNode;
+pub(crate) type NodeFn2 = unsafe extern "C" fn(*mut ffi::Btor, *mut ffi::BoolectorNode, *mut ffi::BoolectorNode) -> *mut ffi::BoolectorNode;
+pub(crate) type NodeFn3 = unsafe extern "C" fn(*mut ffi::Btor, *mut ffi::BoolectorNode, *mut ffi::BoolectorNode, *mut ffi::BoolectorNode) -> *mut ffi::BoolectorNode;
+pub(crate) type NodeCtor1<'a> = for<'b> fn(&'b NodeRef<'a>) -> Node<'a, 'b>;
+pub(crate) type NodeCtor2<'a> = for<'b> fn(&'b NodeRef<'a>, &'b NodeRef<'a>) -> Node<'a, 'b>;
+pub(crate) type NodeFnExt = unsafe extern "C" fn(*mut ffi::Btor, *mut ffi::BoolectorNode, u32) -> *mut ffi::BoolectorNode;
 
-impl<'a> Node<'a> {
+impl<'a> Node<'a, '_> {
     #[inline]
-    fn into_ref_ffi_1(solver: &'a Solver, a: &'a NodeRef<'a>, f: NodeFn1) -> NodeRef<'a> {
+    pub(crate) fn into_ref_ffi_1(solver: &'a Solver, a: &NodeRef<'a>, f: NodeFn1) -> NodeRef<'a> {
         assert_eq!(solver.btor_ptr(), a.solver().btor_ptr());
 
         unsafe {
-            NodeRef::from_ffi(solver, f(solver.btor_ptr(), a.node_ptr()))
+            NodeRef::wrap(solver, f(solver.btor_ptr(), a.node_ptr()))
         }
     }
 
     #[inline]
-    fn into_ref_ffi_2(solver: &'a Solver, a: &'a NodeRef<'a>, b: &'a NodeRef<'a>, f: NodeFn2) -> NodeRef<'a> {
+    pub(crate) fn into_ref_ffi_2(solver: &'a Solver, a: &NodeRef<'a>, b: &NodeRef<'a>, f: NodeFn2) -> NodeRef<'a> {
         assert_eq!(solver.btor_ptr(), a.solver().btor_ptr());
         assert_eq!(solver.btor_ptr(), b.solver().btor_ptr());
 
         unsafe {
-            NodeRef::from_ffi(solver, f(solver.btor_ptr(), a.node_ptr(), b.node_ptr()))
+            NodeRef::wrap(solver, f(solver.btor_ptr(), a.node_ptr(), b.node_ptr()))
         }
     }
 
     #[inline]
-    fn into_ref_ffi_3(solver: &'a Solver, a: &'a NodeRef<'a>, b: &'a NodeRef<'a>, c: &'a NodeRef<'a>, f: NodeFn3) -> NodeRef<'a> {
+    pub(crate) fn into_ref_ffi_3(solver: &'a Solver, a: &NodeRef<'a>, b: &NodeRef<'a>, c: &NodeRef<'a>, f: NodeFn3) -> NodeRef<'a> {
         assert_eq!(solver.btor_ptr(), a.solver().btor_ptr());
         assert_eq!(solver.btor_ptr(), b.solver().btor_ptr());
         assert_eq!(solver.btor_ptr(), c.solver().btor_ptr());
 
         unsafe {
-            NodeRef::from_ffi(solver, f(solver.btor_ptr(), a.node_ptr(), b.node_ptr(), c.node_ptr()))
+            NodeRef::wrap(solver, f(solver.btor_ptr(), a.node_ptr(), b.node_ptr(), c.node_ptr()))
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_ref_ffi_ext(solver: &'a Solver, a: &NodeRef<'a>, width: u32, f: NodeFnExt) -> NodeRef<'a> {
+        assert_eq!(solver.btor_ptr(), a.solver().btor_ptr());
+
+        unsafe {
+            NodeRef::wrap(solver, f(solver.btor_ptr(), a.node_ptr(), width))
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_ref_ffi_slice(solver: &'a Solver, a: &NodeRef<'a>, upper: u32, lower: u32) -> NodeRef<'a> {
+        assert_eq!(solver.btor_ptr(), a.solver().btor_ptr());
+        assert!(lower <= upper && upper < a.width(), "slice bounds out of range");
+
+        unsafe {
+            NodeRef::wrap(solver, ffi::boolector_slice(solver.btor_ptr(), a.node_ptr(), upper, lower))
         }
     }
 }
@@ -1,24 +1,127 @@
-use std::{mem, ops, ptr};
+use std::ffi::{CStr, CString};
+use std::{fmt, mem, ops, ptr};
 
 use boolector_sys as ffi;
 
-use crate::{Node, Solver};
+use crate::macros::Operand;
+use crate::sort_ref::SortShape;
+use crate::{smt2, Node, Solver, SortRef};
 
 /// Expression node reference.
+///
+/// Operators accept owned and borrowed operands alike, and integer literals,
+/// which become constants of the left operand's width.  A literal that does
+/// not fit in that width, read as signed or unsigned, panics instead of being
+/// truncated:
+///
+/// ```
+/// let solver = boolector::Builder::new().finish();
+/// let b8 = solver.sort(boolector::Sort::BitVec(8));
+/// let x = b8.var(Some("x"));
+///
+/// let mut y = &x + 1;
+/// y ^= &x;
+/// y <<= 2;
+/// let z = (y & 0xf0u8) | !x;
+/// let w = &z - 1 + 255;
+///
+/// let overflow = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| &w + 300));
+/// assert!(overflow.is_err());
+/// ```
 pub struct NodeRef<'a> {
-    solver: &'a Solver,
+    pub(crate) solver: &'a Solver,
     node_ptr: *mut ffi::BoolectorNode,
 }
 
 impl<'a> NodeRef<'a> {
     /// Return parent solver instance.
-    pub fn solver(&self) -> &Solver {
+    pub fn solver(&self) -> &'a Solver {
         self.solver
     }
 
+    /// Return the node id.
+    ///
+    /// Boolector represents some negations as inverted references to an
+    /// existing node; such references have the negated id of that node.
+    pub fn id(&self) -> i32 {
+        unsafe {
+            ffi::boolector_get_node_id(self.solver.btor_ptr(), self.node_ptr)
+        }
+    }
+
+    /// Return the symbol associated with this node, if any.
+    pub fn symbol(&self) -> Option<String> {
+        unsafe {
+            let symbol_ptr = ffi::boolector_get_symbol(self.solver.btor_ptr(), self.node_ptr);
+
+            if symbol_ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(symbol_ptr).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Associate a symbol with this node, for example to name an assertion.
+    pub fn set_symbol(&self, symbol: &str) {
+        let cstr = CString::new(symbol).expect("symbol contains a NUL character");
+
+        unsafe {
+            ffi::boolector_set_symbol(self.solver.btor_ptr(), self.node_ptr, cstr.as_ptr());
+        }
+    }
+
+    /// Return the width of this bit vector node, or the width of elements if
+    /// this is an array node.
+    pub fn width(&self) -> u32 {
+        unsafe {
+            ffi::boolector_get_width(self.solver.btor_ptr(), self.node_ptr)
+        }
+    }
+
+    /// Return the sort of this node.
+    pub fn sort(&self) -> SortRef<'a> {
+        let btor_ptr = self.solver.btor_ptr();
+
+        unsafe {
+            let sort_ptr = ffi::boolector_copy_sort(btor_ptr, ffi::boolector_get_sort(btor_ptr, self.node_ptr));
+
+            // The widths of bit vectors and arrays can be read off the node
+            // itself; those of function domains cannot.
+            if ffi::boolector_is_array(btor_ptr, self.node_ptr) {
+                let index_width = ffi::boolector_get_index_width(btor_ptr, self.node_ptr);
+                SortRef::wrap_shaped(self.solver, sort_ptr, SortShape::Array(index_width, self.width()))
+            } else if ffi::boolector_is_fun(btor_ptr, self.node_ptr) {
+                SortRef::wrap(self.solver, sort_ptr)
+            } else {
+                SortRef::wrap_shaped(self.solver, sort_ptr, SortShape::BitVec(self.width()))
+            }
+        }
+    }
+
+    /// Return whether this node has the same sort as `other`.
+    pub fn has_same_sort(&self, other: &NodeRef<'_>) -> bool {
+        assert_eq!(self.solver.btor_ptr(), other.solver.btor_ptr());
+
+        unsafe {
+            ffi::boolector_is_equal_sort(self.solver.btor_ptr(), self.node_ptr, other.node_ptr)
+        }
+    }
+
     /// Construct a node reference from raw `BoolectorNode` pointer and its
     /// parent solver.
+    ///
+    /// This takes back a reference that left through
+    /// [into_ffi](#method.into_ffi), so that it no longer counts as leaked.
     pub unsafe fn from_ffi(solver: &'a Solver, node_ptr: *mut ffi::BoolectorNode) -> Self {
+        solver.reclaim_node(node_ptr);
+        NodeRef::wrap(solver, node_ptr)
+    }
+
+    /// Wrap a reference that the crate obtained from Boolector itself.
+    pub(crate) unsafe fn wrap(solver: &'a Solver, node_ptr: *mut ffi::BoolectorNode) -> Self {
+        solver.observe_node(node_ptr);
+
         NodeRef {
             solver,
             node_ptr,
@@ -28,6 +131,7 @@ impl<'a> NodeRef<'a> {
     /// Consume the node reference and return the underlying raw `BoolectorNode`
     /// pointer.
     pub fn into_ffi(mut self) -> *mut ffi::BoolectorNode {
+        self.solver.escape_node(self.node_ptr);
         mem::replace(&mut self.node_ptr, ptr::null_mut())
     }
 
@@ -48,76 +152,104 @@ impl<'a> Clone for NodeRef<'a> {
     }
 }
 
-impl<'a> Drop for NodeRef<'a> {
-    fn drop(&mut self) {
-        if !self.node_ptr.is_null() {
-            unsafe {
-                ffi::boolector_release(self.solver.btor_ptr(), self.node_ptr);
-            }
+/// Formats the node as an SMT-LIB 2 term, using `let` bindings for shared
+/// subterms, or as its name if it is a variable.
+///
+/// Output longer than a few kilobytes is cut off and ends in `...`.  The
+/// whole term is still written out by Boolector first, so formatting a large
+/// term takes time in proportion to its size.
+///
+/// ```
+/// let solver = boolector::Builder::new().finish();
+/// let b8 = solver.sort(boolector::Sort::BitVec(8));
+/// let x = b8.var(Some("x"));
+/// let y = b8.var(Some("y"));
+/// let sum = &x + &y;
+///
+/// assert_eq!(x.to_string(), "x");
+/// assert_eq!((!&x).to_string(), "(bvnot x)");
+/// assert_eq!((&sum * &sum).to_string(), "(let (($e1 (bvadd x y))) (bvmul $e1 $e1))");
+/// assert_eq!(b8.to_string(), "(_ BitVec 8)");
+/// assert_eq!(
+///     format!("{:?}", x),
+///     format!("NodeRef {{ id: {}, symbol: Some(\"x\"), sort: (_ BitVec 8), width: 8 }}", x.id()),
+/// );
+/// ```
+impl<'a> fmt::Display for NodeRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = unsafe {
+            smt2::dump_node(self.solver.btor_ptr(), self.node_ptr)?
+        };
+
+        // Variables are dumped as their declaration, without inversion, which
+        // Boolector marks in the lowest bit of the pointer.
+        match smt2::declaration(&text) {
+            Some((name, _)) if self.node_ptr as usize & 1 == 1 => write!(f, "(bvnot {})", name),
+            Some((name, _)) => f.write_str(name),
+            None => f.write_str(&text),
         }
     }
 }
 
-impl<'a> ops::Add for &'a NodeRef<'a> {
-    type Output = NodeRef<'a>;
-
-    fn add(self, other: Self) -> Self::Output {
-        Node::Add(self, other).into_ref(self.solver())
+impl<'a> fmt::Debug for NodeRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NodeRef")
+            .field("id", &self.id())
+            .field("symbol", &self.symbol())
+            .field("sort", &format_args!("{}", self.sort()))
+            .field("width", &self.width())
+            .finish()
     }
 }
 
-impl<'a> ops::BitAnd for &'a NodeRef<'a> {
-    type Output = NodeRef<'a>;
-
-    fn bitand(self, other: Self) -> Self::Output {
-        Node::And(self, other).into_ref(self.solver())
+impl<'a> Drop for NodeRef<'a> {
+    fn drop(&mut self) {
+        if !self.node_ptr.is_null() {
+            unsafe {
+                ffi::boolector_release(self.solver.btor_ptr(), self.node_ptr);
+            }
+        }
     }
 }
 
-impl<'a> ops::BitOr for &'a NodeRef<'a> {
-    type Output = NodeRef<'a>;
-
-    fn bitor(self, other: Self) -> Self::Output {
-        Node::Or(self, other).into_ref(self.solver())
+impl<'a> Operand<'a> for NodeRef<'a> {
+    fn node(&self) -> &NodeRef<'a> {
+        self
     }
-}
 
-impl<'a> ops::BitXor for &'a NodeRef<'a> {
-    type Output = NodeRef<'a>;
-
-    fn bitxor(self, other: Self) -> Self::Output {
-        Node::Xor(self, other).into_ref(self.solver())
+    fn from_node(node: NodeRef<'a>) -> Self {
+        node
     }
 }
 
-impl<'a> ops::Mul for &'a NodeRef<'a> {
-    type Output = NodeRef<'a>;
+impl_binary_op!(NodeRef, Add, add, AddAssign, add_assign, |a, b| {
+    Node::Add(a, b).into_ref(a.solver)
+});
 
-    fn mul(self, other: Self) -> Self::Output {
-        Node::Mul(self, other).into_ref(self.solver())
-    }
-}
+impl_binary_op!(NodeRef, BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| {
+    Node::And(a, b).into_ref(a.solver)
+});
 
-impl<'a> ops::Not for &'a NodeRef<'a> {
-    type Output = NodeRef<'a>;
+impl_binary_op!(NodeRef, BitOr, bitor, BitOrAssign, bitor_assign, |a, b| {
+    Node::Or(a, b).into_ref(a.solver)
+});
 
-    fn not(self) -> Self::Output {
-        Node::Not(self).into_ref(self.solver())
-    }
-}
+impl_binary_op!(NodeRef, BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| {
+    Node::Xor(a, b).into_ref(a.solver)
+});
 
-impl<'a> ops::Shl for &'a NodeRef<'a> {
-    type Output = NodeRef<'a>;
+impl_binary_op!(NodeRef, Mul, mul, MulAssign, mul_assign, |a, b| {
+    Node::Mul(a, b).into_ref(a.solver)
+});
 
-    fn shl(self, other: Self) -> Self::Output {
-        Node::Sll(self, other).into_ref(self.solver())
-    }
-}
+impl_unary_op!(NodeRef, Not, not, |a| {
+    Node::Not(a).into_ref(a.solver)
+});
 
-impl<'a> ops::Sub for &'a NodeRef<'a> {
-    type Output = NodeRef<'a>;
+impl_binary_op!(NodeRef, Shl, shl, ShlAssign, shl_assign, |a, b| {
+    a.shift_left(b)
+});
 
-    fn sub(self, other: Self) -> Self::Output {
-        Node::Sub(self, other).into_ref(self.solver())
-    }
-}
+impl_binary_op!(NodeRef, Sub, sub, SubAssign, sub_assign, |a, b| {
+    Node::Sub(a, b).into_ref(a.solver)
+});
@@ -0,0 +1,180 @@
+use std::time::{Duration, Instant};
+
+use boolector_sys as ffi;
+
+use crate::{BitVecValue, Model, Node, NodeRef, SolveResult, Solver, Unsigned};
+
+/// Result of an optimization, see
+/// [Solver::minimize](struct.Solver.html#method.minimize).
+pub struct Optimum<'a> {
+    /// Best objective value found.
+    pub value: BitVecValue,
+
+    /// Model in which the objective takes `value`; it stays valid until the
+    /// next `solve` call.  This is `None` if the timeout fired before such a
+    /// model could be recomputed.
+    pub model: Option<Model<'a>>,
+
+    /// Whether `value` is proven optimal, which is not the case if the
+    /// timeout fired first.
+    pub optimal: bool,
+}
+
+impl Solver {
+    /// Find the smallest unsigned value of `objective` over all models.
+    ///
+    /// Returns `None` if the formula is unsatisfiable, or if the timeout fires
+    /// before any model is found.  If it fires later, the best value found so
+    /// far is returned with `optimal` unset.  The timeout covers the whole
+    /// search, including a final `solve` call that recomputes the model for
+    /// the best value if needed.
+    ///
+    /// Requires incremental solving and model generation.  The search fixes
+    /// bits from the most significant one down with assumptions, so it needs
+    /// at most one `solve` call per bit; assertions are left unchanged.
+    ///
+    /// ```
+    /// use boolector::{BitVecValue, Builder, GenerateModel, Node, Sort, Unsigned};
+    ///
+    /// let solver = Builder::new()
+    ///     .incremental(true)
+    ///     .generate_model(GenerateModel::Asserted)
+    ///     .finish();
+    ///
+    /// let b8 = solver.sort(Sort::BitVec(8));
+    /// let x = b8.var(Some("x"));
+    ///
+    /// // x * 3 overflows for the smallest x above 85.
+    /// let product = solver.node(Node::UMulO(&x, &BitVecValue::from_u64(8, 3).to_node(&solver)));
+    /// solver.assert(&product);
+    ///
+    /// let optimum = solver.minimize(&Unsigned(x.clone()), None).unwrap();
+    /// assert_eq!(optimum.value.to_u64(), Some(86));
+    /// assert!(optimum.optimal && optimum.model.is_some());
+    /// ```
+    pub fn minimize<'a>(&'a self, objective: &Unsigned<'a>, timeout: Option<Duration>) -> Option<Optimum<'a>> {
+        self.optimize(objective, timeout, false, false)
+    }
+
+    /// Find the largest unsigned value of `objective` over all models, see
+    /// [minimize](#method.minimize).
+    pub fn maximize<'a>(&'a self, objective: &Unsigned<'a>, timeout: Option<Duration>) -> Option<Optimum<'a>> {
+        self.optimize(objective, timeout, true, false)
+    }
+
+    /// Find the smallest signed value of `objective` over all models, see
+    /// [minimize](#method.minimize).
+    pub fn minimize_signed<'a>(&'a self, objective: &NodeRef<'a>, timeout: Option<Duration>) -> Option<Optimum<'a>> {
+        self.optimize(objective, timeout, false, true)
+    }
+
+    /// Find the largest signed value of `objective` over all models, see
+    /// [minimize](#method.minimize).
+    pub fn maximize_signed<'a>(&'a self, objective: &NodeRef<'a>, timeout: Option<Duration>) -> Option<Optimum<'a>> {
+        self.optimize(objective, timeout, true, true)
+    }
+
+    fn optimize<'a>(&'a self, objective: &NodeRef<'a>, timeout: Option<Duration>, maximize: bool, signed: bool) -> Option<Optimum<'a>> {
+        assert_eq!(self.btor_ptr(), objective.solver().btor_ptr());
+
+        unsafe {
+            assert!(
+                ffi::boolector_get_opt(self.btor_ptr(), ffi::BtorOption_BTOR_OPT_INCREMENTAL) != 0,
+                "incremental solving is not enabled",
+            );
+            assert!(
+                ffi::boolector_get_opt(self.btor_ptr(), ffi::BtorOption_BTOR_OPT_MODEL_GEN) != 0,
+                "model generation is not enabled",
+            );
+        }
+
+        // Every variant minimizes `key = objective ^ mask` as an unsigned
+        // number: flipping all bits reverses the order, and flipping the sign
+        // bit turns signed order into unsigned order.
+        let width = objective.width();
+        let mut mask = if maximize { BitVecValue::ones(width) } else { BitVecValue::zero(width) };
+
+        if signed {
+            mask.set_bit(width - 1, !maximize);
+        }
+
+        let key = if mask.is_zero() {
+            objective.clone()
+        } else {
+            Node::Xor(objective, &mask.to_node(self)).into_ref(self)
+        };
+
+        let termination = self.termination();
+        termination.set_deadline(timeout.map(|timeout| Instant::now() + timeout));
+        let best = self.minimize_key(&key);
+        termination.set_deadline(None);
+
+        let (best, optimal, model) = best?;
+
+        Some(Optimum {
+            value: best.xor(&mask),
+            model,
+            optimal,
+        })
+    }
+
+    /// Minimize `key` as an unsigned number, returning the best value, whether
+    /// it is proven optimal, and a model for it unless the deadline passed
+    /// first.
+    fn minimize_key<'a>(&'a self, key: &NodeRef<'a>) -> Option<(BitVecValue, bool, Option<Model<'a>>)> {
+        let value_of = |model: &Model| BitVecValue::from_bits(model.bit_vec(key).to_str());
+
+        let (mut best, mut last) = match self.solve() {
+            SolveResult::Sat(Some(model)) => (value_of(&model), Some(model)),
+            _ => return None,
+        };
+
+        let mut optimal = true;
+        let width = key.width();
+
+        for bit in (0..width).rev() {
+            if !best.bit(bit) {
+                continue;
+            }
+
+            // Try to clear this bit while keeping the bits above it.
+            let mut prefix = best.slice(width - 1, bit);
+            prefix.set_bit(0, false);
+
+            let upper = Node::Slice(key, width - 1, bit).into_ref(self);
+            let cond = Node::Eq(&upper, &prefix.to_node(self)).into_ref(self);
+            self.assume(&cond);
+
+            match self.solve() {
+                SolveResult::Sat(Some(model)) => {
+                    best = value_of(&model);
+                    last = Some(model);
+                },
+                SolveResult::Sat(None) => panic!("model generation is not enabled"),
+                SolveResult::Unsat => last = None,
+                SolveResult::Unknown => {
+                    last = None;
+                    optimal = false;
+                    break;
+                },
+            }
+        }
+
+        if last.is_some() {
+            return Some((best, optimal, last));
+        }
+
+        // The last `solve` call did not find `best`, so solve once more for a
+        // model with that value.
+        let fixed = Node::Eq(key, &best.to_node(self)).into_ref(self);
+        self.assume(&fixed);
+
+        let model = match self.solve() {
+            SolveResult::Sat(Some(model)) => Some(model),
+            SolveResult::Unknown => None,
+            _ => panic!("best objective value is no longer satisfiable"),
+        };
+
+        Some((best, optimal, model))
+    }
+}
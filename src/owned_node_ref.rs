@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use boolector_sys as ffi;
+
+use crate::shared_solver::Reference;
+use crate::{NodeRef, SharedSolver, Solver};
+
+/// Expression node reference that keeps its parent solver alive.
+///
+/// Unlike [NodeRef](struct.NodeRef.html), this type has no lifetime parameter,
+/// so it can be stored in long-lived structures and moved between threads.
+/// The parent solver is shared through a
+/// [SharedSolver](struct.SharedSolver.html); the solver instance is deleted
+/// once the last owned reference and the last handle are dropped.
+///
+/// # Example
+///
+/// ```
+/// use boolector::{Builder, Node, OwnedNodeRef, SharedSolver, Sort};
+///
+/// let shared = SharedSolver::new(Builder::new().finish());
+///
+/// let registers: Vec<OwnedNodeRef> = shared.with(|solver| {
+///     let b32 = solver.sort(Sort::BitVec(32));
+///
+///     (0..4)
+///         .map(|i| b32.var(Some(&format!("r{}", i))).to_owned_ref(&shared))
+///         .collect()
+/// });
+///
+/// // The registers keep the solver instance alive.
+/// drop(shared);
+///
+/// let worker = std::thread::spawn(move || {
+///     registers[0].solver().with(|solver| {
+///         let r0 = registers[0].as_node_ref(solver);
+///         let r1 = registers[1].as_node_ref(solver);
+///         let sum = &r0 + &r1;
+///
+///         solver.assert(&solver.node(Node::Eq(&sum, &r0)));
+///         solver.solve().is_sat()
+///     })
+/// });
+///
+/// assert!(worker.join().unwrap());
+/// ```
+#[derive(Clone)]
+pub struct OwnedNodeRef {
+    inner: Arc<Held>,
+}
+
+/// Single Boolector reference shared by all clones of an owned reference.
+pub(crate) struct Held {
+    pub(crate) solver: SharedSolver,
+    pub(crate) reference: Reference,
+}
+
+impl Drop for Held {
+    fn drop(&mut self) {
+        self.solver.release(self.reference);
+    }
+}
+
+impl OwnedNodeRef {
+    /// Create an owned node reference from a borrowed one.  The node must
+    /// belong to the solver behind `solver`, which is locked by the caller.
+    pub fn new(solver: &SharedSolver, node: &NodeRef) -> Self {
+        assert!(solver.holds(node.solver()), "node belongs to another solver");
+
+        let node_ptr = unsafe { ffi::boolector_copy(node.solver().btor_ptr(), node.node_ptr()) };
+
+        OwnedNodeRef {
+            inner: Arc::new(Held {
+                solver: solver.clone(),
+                reference: Reference::Node(node_ptr),
+            }),
+        }
+    }
+
+    /// Return parent solver instance.
+    pub fn solver(&self) -> &SharedSolver {
+        &self.inner.solver
+    }
+
+    /// Return a borrowed reference to the same node.  `solver` must be the
+    /// locked parent solver instance.
+    pub fn as_node_ref<'a>(&self, solver: &'a Solver) -> NodeRef<'a> {
+        assert!(self.inner.solver.holds(solver), "node belongs to another solver");
+
+        unsafe {
+            NodeRef::wrap(solver, ffi::boolector_copy(solver.btor_ptr(), self.node_ptr()))
+        }
+    }
+
+    /// Return the underlying raw `BoolectorNode` pointer.
+    pub fn node_ptr(&self) -> *mut ffi::BoolectorNode {
+        match self.inner.reference {
+            Reference::Node(node_ptr) => node_ptr,
+            Reference::Sort(_) => unreachable!(),
+        }
+    }
+}
+
+impl<'a> NodeRef<'a> {
+    /// Return an owned reference to the same node.  The parent solver of this
+    /// node must be the one behind `solver`.
+    pub fn to_owned_ref(&self, solver: &SharedSolver) -> OwnedNodeRef {
+        OwnedNodeRef::new(solver, self)
+    }
+}
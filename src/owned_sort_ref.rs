@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use boolector_sys as ffi;
+
+use crate::owned_node_ref::Held;
+use crate::shared_solver::Reference;
+use crate::{SharedSolver, Solver, SortRef};
+
+/// Sort reference that keeps its parent solver alive.
+///
+/// This is the sort counterpart of [OwnedNodeRef](struct.OwnedNodeRef.html).
+#[derive(Clone)]
+pub struct OwnedSortRef {
+    inner: Arc<Held>,
+}
+
+impl OwnedSortRef {
+    /// Create an owned sort reference from a borrowed one.  The sort must
+    /// belong to the solver behind `solver`, which is locked by the caller.
+    pub fn new(solver: &SharedSolver, sort: &SortRef) -> Self {
+        assert!(solver.holds(sort.solver()), "sort belongs to another solver");
+
+        let sort_ptr = unsafe { ffi::boolector_copy_sort(sort.solver().btor_ptr(), sort.sort_ptr()) };
+
+        OwnedSortRef {
+            inner: Arc::new(Held {
+                solver: solver.clone(),
+                reference: Reference::Sort(sort_ptr),
+            }),
+        }
+    }
+
+    /// Return parent solver instance.
+    pub fn solver(&self) -> &SharedSolver {
+        &self.inner.solver
+    }
+
+    /// Return a borrowed reference to the same sort.  `solver` must be the
+    /// locked parent solver instance.
+    pub fn as_sort_ref<'a>(&self, solver: &'a Solver) -> SortRef<'a> {
+        assert!(self.inner.solver.holds(solver), "sort belongs to another solver");
+
+        unsafe {
+            SortRef::wrap(solver, ffi::boolector_copy_sort(solver.btor_ptr(), self.sort_ptr()))
+        }
+    }
+
+    /// Return the underlying raw `BoolectorSort` value.
+    pub fn sort_ptr(&self) -> ffi::BoolectorSort {
+        match self.inner.reference {
+            Reference::Sort(sort_ptr) => sort_ptr,
+            Reference::Node(_) => unreachable!(),
+        }
+    }
+}
+
+impl<'a> SortRef<'a> {
+    /// Return an owned reference to the same sort.  The parent solver of this
+    /// sort must be the one behind `solver`.
+    pub fn to_owned_ref(&self, solver: &SharedSolver) -> OwnedSortRef {
+        OwnedSortRef::new(solver, self)
+    }
+}
@@ -0,0 +1,133 @@
+//! Portfolio solving: race several configurations on one formula.
+//!
+//! ```
+//! use boolector::portfolio;
+//! use boolector::{Builder, Config, Engine, Sort};
+//!
+//! let solver = Builder::new().finish();
+//! let b16 = solver.sort(Sort::BitVec(16));
+//! let x = b16.var(Some("x"));
+//! solver.assert(&boolector::Unsigned(&x * &x).lt(&boolector::Unsigned(x.clone())));
+//!
+//! let configs = [
+//!     Config::default(),
+//!     Config { engine: Some(Engine::Prop), ..Config::default() },
+//!     Config { seed: Some(1), rewrite_level: Some(1), ..Config::default() },
+//! ];
+//!
+//! let outcome = portfolio::solve(&solver, &configs).unwrap().unwrap();
+//! assert!(outcome.sat);
+//! println!("won by {:?}", configs[outcome.winner]);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{Builder, Config, SolveResult, Solver};
+
+/// Result of a portfolio run.
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    /// Index of the winning configuration.
+    pub winner: usize,
+
+    /// Winning configuration.
+    pub config: Config,
+
+    /// Whether the formula is satisfiable.
+    pub sat: bool,
+
+    /// Wall time until the winner finished, including copying the formula.
+    pub elapsed: Duration,
+}
+
+/// Failure of one instance of a portfolio run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PortfolioError {
+    /// Index of the failed configuration.
+    pub worker: usize,
+
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for PortfolioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "configuration {}: cannot copy formula: {}", self.worker, self.message)
+    }
+}
+
+impl Error for PortfolioError {}
+
+/// Solve the formula asserted on `solver` with every configuration in
+/// `configs`, each on its own thread, and return the first definite answer.
+///
+/// Each thread gets a fresh instance with the formula copied through
+/// [Solver::dump_smt2](../struct.Solver.html#method.dump_smt2), so `solver`
+/// itself is not solved and must not have pending assumptions.  Once one
+/// instance answers, the others are cancelled through their termination
+/// callbacks; this function returns after all threads have stopped.
+///
+/// Returns `None` if `configs` is empty or no instance reached an answer.  If
+/// an instance cannot take over the formula, the others are cancelled as well
+/// and the first such failure is returned.
+pub fn solve(solver: &Solver, configs: &[Config]) -> Result<Option<Outcome>, PortfolioError> {
+    let formula = solver.dump_smt2();
+    let done = Arc::new(AtomicBool::new(false));
+    let outcome = Mutex::new(None);
+    let failure = Mutex::new(None);
+    let start = Instant::now();
+
+    thread::scope(|scope| {
+        for (index, config) in configs.iter().enumerate() {
+            let formula = &formula;
+            let done = &done;
+            let outcome = &outcome;
+            let failure = &failure;
+
+            scope.spawn(move || {
+                let instance = Builder::new().config(config).finish();
+
+                if let Err(message) = instance.parse_smt2(formula) {
+                    done.store(true, Ordering::Release);
+                    failure
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .get_or_insert(PortfolioError { worker: index, message });
+                    return;
+                }
+
+                let cancelled = Arc::clone(done);
+                instance.set_termination(move || cancelled.load(Ordering::Relaxed));
+
+                if done.load(Ordering::Acquire) {
+                    return;
+                }
+
+                let sat = match instance.solve() {
+                    SolveResult::Sat(_) => true,
+                    SolveResult::Unsat => false,
+                    SolveResult::Unknown => return,
+                };
+
+                if done.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+                    *outcome.lock().unwrap_or_else(PoisonError::into_inner) = Some(Outcome {
+                        winner: index,
+                        config: *config,
+                        sat,
+                        elapsed: start.elapsed(),
+                    });
+                }
+            });
+        }
+    });
+
+    match failure.into_inner().unwrap_or_else(PoisonError::into_inner) {
+        Some(failure) => Err(failure),
+        None => Ok(outcome.into_inner().unwrap_or_else(PoisonError::into_inner)),
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use boolector_sys as ffi;
+
+use crate::{cfile, BitVecValue, SolveResult, Solver};
+
+/// Distinguishes temporary files of concurrent stores within this process.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// On-disk cache of solve results, see
+/// [Solver::solve_cached](struct.Solver.html#method.solve_cached).
+///
+/// Entries are keyed by a hash of the asserted formula in SMT-LIB 2 format,
+/// the solver options, the Boolector version and the version of this crate,
+/// so upgrading either invalidates every entry.  Each entry also holds the
+/// normalized formula and configuration it was stored for, and only answers
+/// whose formula matches exactly are returned, so hash collisions are misses.
+pub struct QueryCache {
+    dir: PathBuf,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl QueryCache {
+    /// Open a cache stored in `dir`, creating the directory if needed.
+    pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+
+        Ok(QueryCache {
+            dir: dir.as_ref().to_owned(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        })
+    }
+
+    /// Return the number of answers taken from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Return the number of answers that had to be solved.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn path(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}", key))
+    }
+
+    fn load(&self, key: u64, header: &str) -> Option<CachedResult> {
+        let contents = fs::read_to_string(self.path(key)).ok()?;
+        let body = contents.strip_prefix(header)?;
+        let mut lines = body.lines();
+
+        match lines.next()? {
+            "unsat" => Some(CachedResult::Unsat),
+            "sat" => Some(CachedResult::Sat(None)),
+            "sat model" => {
+                let mut values = BTreeMap::new();
+
+                for line in lines {
+                    let (bits, symbol) = line.split_once(' ')?;
+                    values.insert(symbol.to_owned(), BitVecValue::from_bits(bits));
+                }
+
+                Some(CachedResult::Sat(Some(CachedModel { values })))
+            },
+            _ => None,
+        }
+    }
+
+    fn store(&self, key: u64, header: &str, result: &CachedResult) -> io::Result<()> {
+        let mut contents = header.to_owned();
+
+        match result {
+            CachedResult::Sat(None) => contents.push_str("sat\n"),
+            CachedResult::Sat(Some(model)) => {
+                contents.push_str("sat model\n");
+
+                for (symbol, value) in &model.values {
+                    let _ = writeln!(contents, "{} {}", value, symbol);
+                }
+            },
+            CachedResult::Unsat => contents.push_str("unsat\n"),
+            CachedResult::Unknown => return Ok(()),
+        }
+
+        // Write to a private file first so that concurrent readers never see
+        // a partial entry.
+        let path = self.path(key);
+        let tmp_path = path.with_extension(format!(
+            "tmp{}-{}",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp_path, contents)?;
+        fs::rename(&tmp_path, &path)
+    }
+}
+
+/// Result of [Solver::solve_cached](struct.Solver.html#method.solve_cached).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CachedResult {
+    /// The formula is satisfiable.  If model generation is enabled, the
+    /// values of bit vector variables are provided.
+    Sat(Option<CachedModel>),
+
+    /// The formula is unsatisfiable.
+    Unsat,
+
+    /// The solver was terminated before reaching a result; such results are
+    /// not cached.
+    Unknown,
+}
+
+impl CachedResult {
+    /// Return whether the result is `Sat`.
+    pub fn is_sat(&self) -> bool {
+        match self {
+            CachedResult::Sat(..) => true,
+            CachedResult::Unsat | CachedResult::Unknown => false,
+        }
+    }
+}
+
+/// Values of named bit vector variables in a cached model.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CachedModel {
+    values: BTreeMap<String, BitVecValue>,
+}
+
+impl CachedModel {
+    /// Return the value of the variable with the given symbol.
+    pub fn get(&self, symbol: &str) -> Option<&BitVecValue> {
+        self.values.get(symbol)
+    }
+
+    /// Iterate over symbols and values, ordered by symbol.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BitVecValue)> {
+        self.values.iter().map(|(symbol, value)| (symbol.as_str(), value))
+    }
+
+    /// Parse the output of `boolector_print_model` in SMT-LIB 2 format,
+    /// skipping everything but bit vector variables.
+    fn parse(output: &str) -> Self {
+        let mut values = BTreeMap::new();
+
+        for line in output.lines() {
+            let line = line.trim();
+
+            let parsed = line
+                .strip_prefix("(define-fun ")
+                .and_then(|rest| rest.split_once(" () (_ BitVec "))
+                .and_then(|(symbol, rest)| {
+                    let bits = rest.split_once(") #b")?.1.strip_suffix(')')?;
+                    Some((symbol.trim_matches('|'), bits))
+                });
+
+            if let Some((symbol, bits)) = parsed {
+                if !symbol.contains('\n') && bits.bytes().all(|bit| bit == b'0' || bit == b'1') {
+                    values.insert(symbol.to_owned(), BitVecValue::from_bits(bits));
+                }
+            }
+        }
+
+        CachedModel { values }
+    }
+}
+
+impl Solver {
+    /// Solve the formula, or return the answer cached for an identical
+    /// formula and configuration.
+    ///
+    /// Answers are cached on disk after solving.  With model generation
+    /// enabled, the cached answer includes the values of bit vector variables
+    /// that have a symbol; array and function values are not cached.  Errors
+    /// reading or writing the cache are treated as misses.
+    ///
+    /// Assumptions are not part of the key, so there must be none pending.
+    ///
+    /// ```
+    /// use boolector::{Builder, CachedResult, GenerateModel, QueryCache, Sort};
+    ///
+    /// let dir = std::env::temp_dir().join(format!("boolector-cache-{}", std::process::id()));
+    /// let cache = QueryCache::open(&dir).unwrap();
+    ///
+    /// let solver = Builder::new().generate_model(GenerateModel::Asserted).finish();
+    /// let b8 = solver.sort(Sort::BitVec(8));
+    /// let x = b8.var(Some("x"));
+    /// solver.assert(&solver.node(boolector::Node::Eq(&(&x * 3), &(&x + 2))));
+    ///
+    /// match solver.solve_cached(&cache) {
+    ///     CachedResult::Sat(Some(model)) => println!("x = {}", model.get("x").unwrap()),
+    ///     _ => panic!("expected a model"),
+    /// }
+    ///
+    /// // The same formula is answered from the cache.
+    /// assert!(solver.solve_cached(&cache).is_sat());
+    /// assert_eq!((cache.misses(), cache.hits()), (1, 1));
+    ///
+    /// std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn solve_cached(&self, cache: &QueryCache) -> CachedResult {
+        let formula = self.dump_smt2();
+        // The entry starts with everything the key is derived from, so that
+        // a colliding entry for another formula is not mistaken for a hit.
+        let header = format!("{}{}\n", self.cache_header(), normalize(&formula));
+        let key = fnv1a(header.bytes());
+
+        if let Some(result) = cache.load(key, &header) {
+            cache.hits.fetch_add(1, Ordering::Relaxed);
+            return result;
+        }
+
+        cache.misses.fetch_add(1, Ordering::Relaxed);
+
+        let result = match self.solve() {
+            SolveResult::Sat(Some(_)) => CachedResult::Sat(Some(CachedModel::parse(&self.print_model()))),
+            SolveResult::Sat(None) => CachedResult::Sat(None),
+            SolveResult::Unsat => CachedResult::Unsat,
+            SolveResult::Unknown => CachedResult::Unknown,
+        };
+
+        let _ = cache.store(key, &header, &result);
+        result
+    }
+
+    /// Describe versions and options that affect answers, one per line.
+    fn cache_header(&self) -> String {
+        let (version, git_id) = unsafe {
+            (
+                CStr::from_ptr(ffi::boolector_version(self.btor_ptr())).to_string_lossy(),
+                CStr::from_ptr(ffi::boolector_git_id(self.btor_ptr())).to_string_lossy(),
+            )
+        };
+
+        let mut header = format!("boolector-rs {}\nboolector {} {}\n", env!("CARGO_PKG_VERSION"), version, git_id);
+
+        for opt in 0..ffi::BtorOption_BTOR_OPT_NUM_OPTS {
+            if opt == ffi::BtorOption_BTOR_OPT_VERBOSITY || opt == ffi::BtorOption_BTOR_OPT_LOGLEVEL {
+                continue;
+            }
+
+            let value = unsafe {
+                ffi::boolector_get_opt(self.btor_ptr(), opt)
+            };
+
+            let _ = write!(header, "{}={} ", opt, value);
+        }
+
+        header.push('\n');
+        header
+    }
+
+    fn print_model(&self) -> String {
+        let format = CString::new("smt2").unwrap();
+
+        unsafe {
+            let file = libc::tmpfile();
+
+            if file.is_null() {
+                return String::new();
+            }
+
+            ffi::boolector_print_model(self.btor_ptr(), format.as_ptr() as *mut _, file as *mut ffi::FILE);
+            cfile::read_and_close(file)
+        }
+    }
+}
+
+/// Drop comments and collapse whitespace, so that formatting differences
+/// between dumps do not change the key.
+fn normalize(formula: &str) -> String {
+    formula
+        .lines()
+        .filter(|line| !line.trim_start().starts_with(';'))
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 64-bit FNV-1a hash.
+fn fnv1a<I: IntoIterator<Item = u8>>(bytes: I) -> u64 {
+    bytes.into_iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
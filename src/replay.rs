@@ -0,0 +1,661 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::io::{self, BufRead};
+use std::{fmt, ptr};
+
+use boolector_sys as ffi;
+
+use crate::node::{NodeFn1, NodeFn2, NodeFn3};
+use crate::{BitVecAssignment, Builder, Node, NodeRef, Solver, Sort, SortRef};
+
+/// Summary of a replayed API trace.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ReplaySummary {
+    /// Number of replayed API calls.
+    pub calls: usize,
+
+    /// Results of `sat` calls in trace order, `true` for satisfiable.
+    pub solve_results: Vec<bool>,
+}
+
+/// Error produced while replaying an API trace.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The trace could not be read.
+    Io(io::Error),
+
+    /// A trace line could not be parsed or replayed.
+    Line {
+        /// One-based line number.
+        line: usize,
+
+        /// Description of the problem.
+        message: String,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "cannot read API trace: {}", e),
+            ReplayError::Line { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for ReplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReplayError::Io(e) => Some(e),
+            ReplayError::Line { .. } => None,
+        }
+    }
+}
+
+/// Re-execute a Boolector API trace through the bindings.
+///
+/// The trace is expected in the format written by
+/// [Builder::trace_api](struct.Builder.html#method.trace_api) or by Boolector
+/// itself when `BTORAPITRACE` is set.  Every `new` call starts a fresh solver
+/// instance that lives until the matching `delete` call.  Calls that only query
+/// the instance (`get_*`, `is_*`, `print_*`, `dump_*`) are skipped.
+///
+/// ```
+/// use boolector::{Builder, Node, Sort};
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// let path = std::env::temp_dir().join(format!("boolector-trace-{}", std::process::id()));
+/// {
+///     let solver = Builder::new().trace_api(&path).unwrap().finish();
+///
+///     let b8 = solver.sort(Sort::BitVec(8));
+///     let x = b8.var(Some("x"));
+///     let y = b8.var(Some("y"));
+///     let sum = &x + &y;
+///     let wraps = solver.node(Node::ULt(&sum, &x));
+///
+///     solver.assert(&wraps);
+///     assert!(solver.solve().is_sat());
+/// }
+///
+/// let summary = boolector::replay(BufReader::new(File::open(&path).unwrap())).unwrap();
+/// assert_eq!(summary.solve_results, vec![true]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn replay<R: BufRead>(input: R) -> Result<ReplaySummary, ReplayError> {
+    let mut lines = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.map_err(ReplayError::Io)?;
+
+        if !line.trim().is_empty() {
+            lines.push((index + 1, line));
+        }
+    }
+
+    let mut summary = ReplaySummary::default();
+    let mut rest = &lines[..];
+
+    while let Some(((line, text), tail)) = rest.split_first() {
+        let call = Call::parse(*line, text);
+
+        match call.function {
+            "new" => {
+                summary.calls += 1;
+                rest = replay_instance(tail, &mut summary)?;
+            },
+            "return" => rest = tail,
+            function => {
+                return Err(call.error(format!("expected `new`, found `{}`", function)));
+            },
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Replay calls on a single instance and return the lines that follow its
+/// `delete` call.
+fn replay_instance<'l>(
+    lines: &'l [(usize, String)],
+    summary: &mut ReplaySummary,
+) -> Result<&'l [(usize, String)], ReplayError> {
+    let solver = Builder::new().finish();
+    let mut instance = Instance {
+        solver: &solver,
+        nodes: HashMap::new(),
+        sorts: HashMap::new(),
+        pending: None,
+    };
+
+    for (index, (line, text)) in lines.iter().enumerate() {
+        let call = Call::parse(*line, text);
+
+        if call.function == "return" {
+            instance.bind_return(&call);
+            continue;
+        }
+
+        summary.calls += 1;
+
+        if call.function == "delete" {
+            return Ok(&lines[index + 1..]);
+        }
+
+        instance.pending = instance.execute(&call, summary)?;
+    }
+
+    Ok(&[])
+}
+
+/// Single line of an API trace.
+struct Call<'t> {
+    line: usize,
+    function: &'t str,
+    args: Vec<&'t str>,
+}
+
+impl<'t> Call<'t> {
+    fn parse(line: usize, text: &'t str) -> Self {
+        let mut tokens = text.split_whitespace();
+        let function = tokens.next().unwrap_or("");
+
+        // All functions except `new` take the instance pointer first.
+        if function != "new" && function != "return" {
+            tokens.next();
+        }
+
+        Call {
+            line,
+            function,
+            args: tokens.collect(),
+        }
+    }
+
+    fn error(&self, message: String) -> ReplayError {
+        ReplayError::Line {
+            line: self.line,
+            message,
+        }
+    }
+
+    fn arg(&self, index: usize) -> Result<&'t str, ReplayError> {
+        self.args
+            .get(index)
+            .cloned()
+            .ok_or_else(|| self.error(format!("missing argument {} to `{}`", index + 1, self.function)))
+    }
+
+    fn number<T: std::str::FromStr>(&self, index: usize) -> Result<T, ReplayError> {
+        let arg = self.arg(index)?;
+
+        arg.parse()
+            .map_err(|_| self.error(format!("invalid number `{}`", arg)))
+    }
+
+    fn string(&self, index: usize) -> Result<CString, ReplayError> {
+        CString::new(self.arg(index)?)
+            .map_err(|_| self.error("unexpected NUL character".to_string()))
+    }
+
+    fn symbol(&self, index: usize) -> Result<Option<&'t str>, ReplayError> {
+        match self.args.get(index).cloned() {
+            None | Some("(null)") => Ok(None),
+            Some(symbol) => Ok(Some(symbol)),
+        }
+    }
+}
+
+/// Parse a handle token such as `n12@0x5555` with the given prefix.
+fn handle_id(token: &str, prefix: char) -> Option<i32> {
+    token
+        .strip_prefix(prefix)?
+        .split('@')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Result of a replayed call that may be bound by a following `return` line.
+enum Handle<'a> {
+    Node(NodeRef<'a>),
+    Sort(SortRef<'a>),
+}
+
+/// Handles of a solver instance, keyed by their identifiers in the trace.
+struct Instance<'a> {
+    solver: &'a Solver,
+    nodes: HashMap<i32, NodeRef<'a>>,
+    sorts: HashMap<i32, SortRef<'a>>,
+    pending: Option<Handle<'a>>,
+}
+
+impl<'a> Instance<'a> {
+    fn bind_return(&mut self, call: &Call) {
+        let token = call.args.first().cloned().unwrap_or("");
+
+        match self.pending.take() {
+            Some(Handle::Node(node)) => {
+                if let Some(id) = handle_id(token, 'n') {
+                    self.nodes.insert(id, node);
+                }
+            },
+            Some(Handle::Sort(sort)) => {
+                if let Some(id) = handle_id(token, 's') {
+                    self.sorts.insert(id, sort);
+                }
+            },
+            None => {},
+        }
+    }
+
+    fn node(&mut self, call: &Call, index: usize) -> Result<&NodeRef<'a>, ReplayError> {
+        let token = call.arg(index)?;
+        let id = handle_id(token, 'n')
+            .ok_or_else(|| call.error(format!("expected a node, found `{}`", token)))?;
+
+        // Inverted nodes are referred to by negated identifiers.
+        if !self.nodes.contains_key(&id) && self.nodes.contains_key(&-id) {
+            let inverted = Node::into_ref_ffi_1(self.solver, &self.nodes[&-id], ffi::boolector_not);
+            self.nodes.insert(id, inverted);
+        }
+
+        self.nodes
+            .get(&id)
+            .ok_or_else(|| call.error(format!("unknown node `{}`", token)))
+    }
+
+    fn node_ptr(&mut self, call: &Call, index: usize) -> Result<*mut ffi::BoolectorNode, ReplayError> {
+        self.node(call, index).map(NodeRef::node_ptr)
+    }
+
+    fn sort(&self, call: &Call, index: usize) -> Result<&SortRef<'a>, ReplayError> {
+        let token = call.arg(index)?;
+
+        handle_id(token, 's')
+            .and_then(|id| self.sorts.get(&id))
+            .ok_or_else(|| call.error(format!("unknown sort `{}`", token)))
+    }
+
+    /// Look up an option given by number or, as Boolector traces it, by its
+    /// long name.
+    fn option(&self, call: &Call, index: usize) -> Result<ffi::BtorOption, ReplayError> {
+        let name = call.arg(index)?;
+
+        if let Ok(option) = name.parse() {
+            return Ok(option);
+        }
+
+        let btor = self.solver.btor_ptr();
+
+        unsafe {
+            let mut option = ffi::boolector_first_opt(btor);
+
+            while option < ffi::BtorOption_BTOR_OPT_NUM_OPTS {
+                if CStr::from_ptr(ffi::boolector_get_opt_lng(btor, option)).to_bytes() == name.as_bytes() {
+                    return Ok(option);
+                }
+
+                option = ffi::boolector_next_opt(btor, option);
+            }
+        }
+
+        Err(call.error(format!("unknown option `{}`", name)))
+    }
+
+    fn new_node(&self, node_ptr: *mut ffi::BoolectorNode) -> Option<Handle<'a>> {
+        Some(Handle::Node(unsafe { NodeRef::wrap(self.solver, node_ptr) }))
+    }
+
+    fn new_sort(&self, sort_ptr: ffi::BoolectorSort) -> Option<Handle<'a>> {
+        Some(Handle::Sort(unsafe { SortRef::wrap(self.solver, sort_ptr) }))
+    }
+
+    fn execute(&mut self, call: &Call, summary: &mut ReplaySummary) -> Result<Option<Handle<'a>>, ReplayError> {
+        let btor = self.solver.btor_ptr();
+
+        if let Some(f) = unary_fn(call.function) {
+            let a = self.node(call, 0)?.clone();
+            return Ok(Some(Handle::Node(Node::into_ref_ffi_1(self.solver, &a, f))));
+        }
+
+        if let Some(f) = binary_fn(call.function) {
+            let a = self.node(call, 0)?.clone();
+            let b = self.node(call, 1)?.clone();
+            return Ok(Some(Handle::Node(Node::into_ref_ffi_2(self.solver, &a, &b, f))));
+        }
+
+        if let Some(f) = ternary_fn(call.function) {
+            let a = self.node(call, 0)?.clone();
+            let b = self.node(call, 1)?.clone();
+            let c = self.node(call, 2)?.clone();
+            return Ok(Some(Handle::Node(Node::into_ref_ffi_3(self.solver, &a, &b, &c, f))));
+        }
+
+        let handle = match call.function {
+            "set_opt" => {
+                let option = self.option(call, 0)?;
+                let value = call.number(call.args.len().saturating_sub(1))?;
+
+                unsafe {
+                    ffi::boolector_set_opt(btor, option, value);
+                }
+
+                None
+            },
+            "set_sat_solver" => {
+                let name = call.string(0)?;
+
+                unsafe {
+                    ffi::boolector_set_sat_solver(btor, name.as_ptr());
+                }
+
+                None
+            },
+            "bool_sort" => self.new_sort(unsafe { ffi::boolector_bool_sort(btor) }),
+            "bitvec_sort" => {
+                Some(Handle::Sort(Sort::BitVec(call.number(0)?).into_ref(self.solver)))
+            },
+            "array_sort" => {
+                let index = self.sort(call, 0)?.sort_ptr();
+                let element = self.sort(call, 1)?.sort_ptr();
+
+                self.new_sort(unsafe { ffi::boolector_array_sort(btor, index, element) })
+            },
+            "fun_sort" => {
+                let mut sorts = Vec::new();
+
+                for (index, token) in call.args.iter().enumerate() {
+                    if token.starts_with('s') {
+                        sorts.push(self.sort(call, index)?.sort_ptr());
+                    }
+                }
+
+                let codomain = sorts
+                    .pop()
+                    .ok_or_else(|| call.error("missing codomain sort".to_string()))?;
+
+                self.new_sort(unsafe {
+                    ffi::boolector_fun_sort(btor, sorts.as_mut_ptr(), sorts.len() as u32, codomain)
+                })
+            },
+            "copy_sort" => Some(Handle::Sort(self.sort(call, 0)?.clone())),
+            "release_sort" | "release" | "release_all" => None,
+            "var" | "array" => {
+                let symbol = call.symbol(1)?;
+                Some(Handle::Node(self.sort(call, 0)?.var(symbol)))
+            },
+            "uf" => {
+                let sort = self.sort(call, 0)?.sort_ptr();
+                let symbol = match call.symbol(1)? {
+                    Some(_) => Some(call.string(1)?),
+                    None => None,
+                };
+                let symbol_ptr = symbol.as_ref().map_or(ptr::null(), |s| s.as_ptr());
+
+                self.new_node(unsafe { ffi::boolector_uf(btor, sort, symbol_ptr) })
+            },
+            "param" => {
+                let sort = self.sort(call, 0)?.sort_ptr();
+                let symbol = match call.symbol(1)? {
+                    Some(_) => Some(call.string(1)?),
+                    None => None,
+                };
+                let symbol_ptr = symbol.as_ref().map_or(ptr::null(), |s| s.as_ptr());
+
+                self.new_node(unsafe { ffi::boolector_param(btor, sort, symbol_ptr) })
+            },
+            "fun" => {
+                let count: usize = call.number(0)?;
+                let mut params = Vec::with_capacity(count);
+
+                for index in 1..=count {
+                    params.push(self.node_ptr(call, index)?);
+                }
+
+                let body = self.node_ptr(call, count + 1)?;
+
+                self.new_node(unsafe {
+                    ffi::boolector_fun(btor, params.as_mut_ptr(), count as u32, body)
+                })
+            },
+            "copy" => Some(Handle::Node(self.node(call, 0)?.clone())),
+            "true" => self.new_node(unsafe { ffi::boolector_true(btor) }),
+            "false" => self.new_node(unsafe { ffi::boolector_false(btor) }),
+            "const" => {
+                let bits = call.string(0)?;
+                self.new_node(unsafe { ffi::boolector_const(btor, bits.as_ptr()) })
+            },
+            "constd" | "consth" => {
+                let sort = self.sort(call, 0)?.sort_ptr();
+                let digits = call.string(1)?;
+
+                self.new_node(unsafe {
+                    if call.function == "constd" {
+                        ffi::boolector_constd(btor, sort, digits.as_ptr())
+                    } else {
+                        ffi::boolector_consth(btor, sort, digits.as_ptr())
+                    }
+                })
+            },
+            "zero" | "one" | "ones" | "min_signed" | "max_signed" => {
+                let sort = self.sort(call, 0)?.sort_ptr();
+
+                self.new_node(unsafe {
+                    match call.function {
+                        "zero" => ffi::boolector_zero(btor, sort),
+                        "one" => ffi::boolector_one(btor, sort),
+                        "ones" => ffi::boolector_ones(btor, sort),
+                        "min_signed" => ffi::boolector_min_signed(btor, sort),
+                        _ => ffi::boolector_max_signed(btor, sort),
+                    }
+                })
+            },
+            "unsigned_int" | "int" => {
+                let sort = self.sort(call, 1)?.sort_ptr();
+
+                self.new_node(unsafe {
+                    if call.function == "int" {
+                        ffi::boolector_int(btor, call.number(0)?, sort)
+                    } else {
+                        ffi::boolector_unsigned_int(btor, call.number(0)?, sort)
+                    }
+                })
+            },
+            "slice" => {
+                let a = self.node_ptr(call, 0)?;
+                let (upper, lower) = (call.number(1)?, call.number(2)?);
+
+                self.new_node(unsafe { ffi::boolector_slice(btor, a, upper, lower) })
+            },
+            "uext" | "sext" => {
+                let a = self.node_ptr(call, 0)?;
+                let width = call.number(1)?;
+
+                self.new_node(unsafe {
+                    if call.function == "uext" {
+                        ffi::boolector_uext(btor, a, width)
+                    } else {
+                        ffi::boolector_sext(btor, a, width)
+                    }
+                })
+            },
+            "apply" => {
+                let mut args = Vec::new();
+
+                for (index, token) in call.args.iter().enumerate() {
+                    if token.starts_with('n') {
+                        args.push(self.node_ptr(call, index)?);
+                    }
+                }
+
+                let fun = args
+                    .pop()
+                    .ok_or_else(|| call.error("missing function node".to_string()))?;
+
+                self.new_node(unsafe {
+                    ffi::boolector_apply(btor, args.as_mut_ptr(), args.len() as u32, fun)
+                })
+            },
+            "set_symbol" => {
+                let node = self.node_ptr(call, 0)?;
+                let symbol = call.string(1)?;
+
+                unsafe {
+                    ffi::boolector_set_symbol(btor, node, symbol.as_ptr());
+                }
+
+                None
+            },
+            "assert" | "assume" => {
+                let node = self.node_ptr(call, 0)?;
+
+                unsafe {
+                    if call.function == "assert" {
+                        ffi::boolector_assert(btor, node);
+                    } else {
+                        ffi::boolector_assume(btor, node);
+                    }
+                }
+
+                None
+            },
+            "push" | "pop" => {
+                let level = call.number(0)?;
+
+                unsafe {
+                    if call.function == "push" {
+                        ffi::boolector_push(btor, level);
+                    } else {
+                        ffi::boolector_pop(btor, level);
+                    }
+                }
+
+                None
+            },
+            "sat" => {
+                summary.solve_results.push(self.solver.solve().is_sat());
+                None
+            },
+            "limited_sat" => {
+                let (lod_limit, sat_limit) = (call.number(0)?, call.number(1)?);
+                let result = unsafe { ffi::boolector_limited_sat(btor, lod_limit, sat_limit) };
+
+                summary.solve_results.push(result == ffi::BtorSolverResult_BTOR_RESULT_SAT as i32);
+                None
+            },
+            "failed" => {
+                let node = self.node_ptr(call, 0)?;
+
+                unsafe {
+                    ffi::boolector_failed(btor, node);
+                }
+
+                None
+            },
+            "fixate_assumptions" | "reset_assumptions" | "simplify" => {
+                unsafe {
+                    match call.function {
+                        "fixate_assumptions" => ffi::boolector_fixate_assumptions(btor),
+                        "reset_assumptions" => ffi::boolector_reset_assumptions(btor),
+                        _ => {
+                            ffi::boolector_simplify(btor);
+                        },
+                    }
+                }
+
+                None
+            },
+            "bv_assignment" => {
+                let node = self.node_ptr(call, 0)?;
+
+                drop(BitVecAssignment {
+                    solver: self.solver,
+                    value_ptr: unsafe { ffi::boolector_bv_assignment(btor, node) },
+                });
+
+                None
+            },
+            function if is_query(function) => None,
+            function => return Err(call.error(format!("unsupported function `{}`", function))),
+        };
+
+        Ok(handle)
+    }
+}
+
+/// Return whether a traced function does not modify the instance.
+fn is_query(function: &str) -> bool {
+    ["get_", "is_", "print_", "dump_", "free_"]
+        .iter()
+        .any(|prefix| function.starts_with(prefix))
+}
+
+fn unary_fn(function: &str) -> Option<NodeFn1> {
+    Some(match function {
+        "dec" => ffi::boolector_dec,
+        "inc" => ffi::boolector_inc,
+        "neg" => ffi::boolector_neg,
+        "not" => ffi::boolector_not,
+        "redand" => ffi::boolector_redand,
+        "redor" => ffi::boolector_redor,
+        "redxor" => ffi::boolector_redxor,
+        _ => return None,
+    })
+}
+
+fn binary_fn(function: &str) -> Option<NodeFn2> {
+    Some(match function {
+        "add" => ffi::boolector_add,
+        "and" => ffi::boolector_and,
+        "concat" => ffi::boolector_concat,
+        "eq" => ffi::boolector_eq,
+        "iff" => ffi::boolector_iff,
+        "implies" => ffi::boolector_implies,
+        "mul" => ffi::boolector_mul,
+        "nand" => ffi::boolector_nand,
+        "ne" => ffi::boolector_ne,
+        "nor" => ffi::boolector_nor,
+        "or" => ffi::boolector_or,
+        "read" => ffi::boolector_read,
+        "rol" => ffi::boolector_rol,
+        "ror" => ffi::boolector_ror,
+        "saddo" => ffi::boolector_saddo,
+        "sdiv" => ffi::boolector_sdiv,
+        "sdivo" => ffi::boolector_sdivo,
+        "sgt" => ffi::boolector_sgt,
+        "sgte" => ffi::boolector_sgte,
+        "sll" => ffi::boolector_sll,
+        "slt" => ffi::boolector_slt,
+        "slte" => ffi::boolector_slte,
+        "smod" => ffi::boolector_smod,
+        "smulo" => ffi::boolector_smulo,
+        "sra" => ffi::boolector_sra,
+        "srem" => ffi::boolector_srem,
+        "srl" => ffi::boolector_srl,
+        "ssubo" => ffi::boolector_ssubo,
+        "sub" => ffi::boolector_sub,
+        "uaddo" => ffi::boolector_uaddo,
+        "udiv" => ffi::boolector_udiv,
+        "ugt" => ffi::boolector_ugt,
+        "ugte" => ffi::boolector_ugte,
+        "ult" => ffi::boolector_ult,
+        "ulte" => ffi::boolector_ulte,
+        "umulo" => ffi::boolector_umulo,
+        "urem" => ffi::boolector_urem,
+        "usubo" => ffi::boolector_usubo,
+        "xnor" => ffi::boolector_xnor,
+        "xor" => ffi::boolector_xor,
+        _ => return None,
+    })
+}
+
+fn ternary_fn(function: &str) -> Option<NodeFn3> {
+    Some(match function {
+        "cond" => ffi::boolector_cond,
+        "write" => ffi::boolector_write,
+        _ => return None,
+    })
+}
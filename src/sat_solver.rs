@@ -0,0 +1,34 @@
+/// SAT solver backend.
+///
+/// Boolector aborts if the chosen backend was not compiled into the linked
+/// library.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SatSolver {
+    /// Lingeling.
+    Lingeling,
+
+    /// PicoSAT.
+    PicoSat,
+
+    /// MiniSat.
+    MiniSat,
+
+    /// CaDiCaL.
+    CaDiCaL,
+
+    /// CryptoMiniSat.
+    CryptoMiniSat,
+}
+
+impl SatSolver {
+    /// Return the name Boolector uses for this backend.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            SatSolver::Lingeling => "lingeling",
+            SatSolver::PicoSat => "picosat",
+            SatSolver::MiniSat => "minisat",
+            SatSolver::CaDiCaL => "cadical",
+            SatSolver::CryptoMiniSat => "cms",
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::{mem, ptr};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+
+use boolector_sys as ffi;
+
+use crate::Solver;
+
+/// Solver instance that can be shared between threads.
+///
+/// `Solver` is `Send` but not `Sync`: node and sort references borrow their
+/// parent solver, and Boolector does not allow concurrent calls on one
+/// instance.  This wrapper serializes access with a mutex; references created
+/// while holding the lock borrow the guard and cannot outlive it.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+///
+/// let shared = boolector::SharedSolver::new(boolector::Builder::new().finish());
+///
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let shared = shared.clone();
+///
+///         thread::spawn(move || {
+///             shared.with(|solver| {
+///                 let b8 = solver.sort(boolector::Sort::BitVec(8));
+///                 let x = b8.var(Some(&format!("x{}", i)));
+///                 let y = b8.var(Some(&format!("y{}", i)));
+///                 solver.assert(&solver.node(boolector::Node::ULt(&x, &y)));
+///             });
+///         })
+///     })
+///     .collect();
+///
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+///
+/// assert!(shared.lock().solve().is_sat());
+/// ```
+#[derive(Clone)]
+pub struct SharedSolver {
+    inner: Arc<Shared>,
+}
+
+struct Shared {
+    solver: Mutex<Solver>,
+    btor_ptr: usize,
+    // References dropped while the solver was locked, released on the next
+    // lock.
+    released: Mutex<Vec<Reference>>,
+}
+
+/// Boolector reference held by an owned node or sort handle.
+#[derive(Clone, Copy)]
+pub(crate) enum Reference {
+    Node(*mut ffi::BoolectorNode),
+    Sort(ffi::BoolectorSort),
+}
+
+// The pointers are only passed to Boolector while the solver is locked.
+unsafe impl Send for Reference {}
+unsafe impl Sync for Reference {}
+
+impl Reference {
+    unsafe fn release(self, btor_ptr: *mut ffi::Btor) {
+        match self {
+            Reference::Node(node_ptr) => ffi::boolector_release(btor_ptr, node_ptr),
+            Reference::Sort(sort_ptr) => ffi::boolector_release_sort(btor_ptr, sort_ptr),
+        }
+    }
+}
+
+impl SharedSolver {
+    /// Wrap a solver instance for sharing.
+    pub fn new(solver: Solver) -> Self {
+        SharedSolver {
+            inner: Arc::new(Shared {
+                btor_ptr: solver.btor_ptr() as usize,
+                solver: Mutex::new(solver),
+                released: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Lock the solver instance, blocking until it is available.
+    ///
+    /// A panic in another thread holding the lock does not poison the
+    /// instance: Boolector aborts the process on its own errors, so a panic
+    /// can only happen between Boolector calls and leaves the instance usable.
+    /// Whatever the panicking thread had asserted, assumed or pushed stays in
+    /// place, though.
+    pub fn lock(&self) -> MutexGuard<'_, Solver> {
+        let solver = self.inner.solver.lock().unwrap_or_else(PoisonError::into_inner);
+        self.inner.release_pending(&solver);
+        solver
+    }
+
+    /// Lock the solver instance and run `f` on it.
+    pub fn with<R, F: FnOnce(&Solver) -> R>(&self, f: F) -> R {
+        f(&self.lock())
+    }
+
+    /// Return the solver instance if this is the only handle to it.
+    ///
+    /// Owned node and sort references keep a handle, see
+    /// [OwnedNodeRef](struct.OwnedNodeRef.html).
+    pub fn try_unwrap(self) -> Result<Solver, Self> {
+        match Arc::try_unwrap(self.inner) {
+            Ok(mut inner) => {
+                inner.release_all_pending();
+
+                // A solver without an instance is a no-op when dropped.
+                let empty = unsafe { Solver::from_ffi(ptr::null_mut()) };
+                let solver = mem::replace(&mut inner.solver, Mutex::new(empty));
+                Ok(solver.into_inner().unwrap_or_else(PoisonError::into_inner))
+            },
+            Err(inner) => Err(SharedSolver { inner }),
+        }
+    }
+
+    /// Return whether `solver` is the instance behind this handle.
+    pub(crate) fn holds(&self, solver: &Solver) -> bool {
+        self.inner.btor_ptr == solver.btor_ptr() as usize
+    }
+
+    /// Release a reference now if the solver is free, or on its next lock
+    /// otherwise.  The lock may be held by the calling thread itself, so this
+    /// never blocks on it.
+    pub(crate) fn release(&self, reference: Reference) {
+        self.inner
+            .released
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(reference);
+
+        match self.inner.solver.try_lock() {
+            Ok(solver) => self.inner.release_pending(&solver),
+            Err(TryLockError::Poisoned(e)) => self.inner.release_pending(&e.into_inner()),
+            Err(TryLockError::WouldBlock) => {},
+        }
+    }
+}
+
+impl Shared {
+    fn release_pending(&self, solver: &Solver) {
+        let pending = mem::take(&mut *self.released.lock().unwrap_or_else(PoisonError::into_inner));
+
+        for reference in pending {
+            unsafe {
+                reference.release(solver.btor_ptr());
+            }
+        }
+    }
+
+    fn release_all_pending(&mut self) {
+        let solver = self.solver.get_mut().unwrap_or_else(PoisonError::into_inner);
+        let pending = mem::take(self.released.get_mut().unwrap_or_else(PoisonError::into_inner));
+
+        for reference in pending {
+            unsafe {
+                reference.release(solver.btor_ptr());
+            }
+        }
+    }
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        self.release_all_pending();
+    }
+}
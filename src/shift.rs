@@ -0,0 +1,148 @@
+use crate::node::NodeCtor2;
+use crate::{BitVecValue, Node, NodeRef};
+
+/// Shifts and rotates for operands of arbitrary widths.
+///
+/// Unlike the raw `Node` variants, these accept a bit vector of any width `n`
+/// and a shift amount of any width.  The result has width `n` and follows
+/// SMT-LIB semantics: the amount is read as an unsigned number, shifting by `n`
+/// or more bits yields zero (or copies of the sign bit for arithmetic shifts),
+/// and rotating by `k` bits is the same as rotating by `k mod n` bits.
+impl<'a> NodeRef<'a> {
+    /// Shift left, filling with zeros.
+    pub fn shift_left(&self, amount: &NodeRef<'a>) -> NodeRef<'a> {
+        self.shift(amount, false, |a, b| Node::Sll(a, b))
+    }
+
+    /// Logical shift right, filling with zeros.
+    pub fn shift_right_logical(&self, amount: &NodeRef<'a>) -> NodeRef<'a> {
+        self.shift(amount, false, |a, b| Node::Srl(a, b))
+    }
+
+    /// Arithmetic shift right, filling with copies of the sign bit.
+    pub fn shift_right_arith(&self, amount: &NodeRef<'a>) -> NodeRef<'a> {
+        self.shift(amount, true, |a, b| Node::Sra(a, b))
+    }
+
+    /// Rotate left by a variable amount.
+    pub fn rotate_left(&self, amount: &NodeRef<'a>) -> NodeRef<'a> {
+        self.rotate(amount, true)
+    }
+
+    /// Rotate right by a variable amount.
+    pub fn rotate_right(&self, amount: &NodeRef<'a>) -> NodeRef<'a> {
+        self.rotate(amount, false)
+    }
+
+    /// Rotate left by a constant amount.
+    pub fn rotate_left_by(&self, amount: u32) -> NodeRef<'a> {
+        let width = self.width();
+        let amount = amount % width;
+
+        if amount == 0 {
+            return self.clone();
+        }
+
+        let high = Node::Slice(self, width - amount - 1, 0).into_ref(self.solver);
+        let low = Node::Slice(self, width - 1, width - amount).into_ref(self.solver);
+
+        Node::Concat(&high, &low).into_ref(self.solver)
+    }
+
+    /// Rotate right by a constant amount.
+    pub fn rotate_right_by(&self, amount: u32) -> NodeRef<'a> {
+        let width = self.width();
+
+        self.rotate_left_by(width - amount % width)
+    }
+
+    /// Shift by an arbitrary amount using a Boolector shift `f`, which needs a
+    /// power-of-two width and a `log2`-wide amount.
+    fn shift(&self, amount: &NodeRef<'a>, signed: bool, f: NodeCtor2<'a>) -> NodeRef<'a> {
+        let solver = self.solver;
+        let width = self.width();
+        let amount_width = amount.width();
+
+        // Extend the operand to a power of two; for amounts in `width..padded`
+        // the extension bits shift into the result, which gives the correct
+        // fill.
+        let padded = width.next_power_of_two().max(2);
+        let log2 = padded.trailing_zeros();
+
+        let operand = if padded > width {
+            let ext = if signed { Node::SExt } else { Node::UExt };
+            ext(self, padded - width).into_ref(solver)
+        } else {
+            self.clone()
+        };
+
+        let short_amount = if amount_width > log2 {
+            Node::Slice(amount, log2 - 1, 0).into_ref(solver)
+        } else if amount_width < log2 {
+            Node::UExt(amount, log2 - amount_width).into_ref(solver)
+        } else {
+            amount.clone()
+        };
+
+        let shifted = f(&operand, &short_amount).into_ref(solver);
+        let shifted = if padded > width {
+            Node::Slice(&shifted, width - 1, 0).into_ref(solver)
+        } else {
+            shifted
+        };
+
+        if amount_width <= log2 {
+            return shifted;
+        }
+
+        // Amounts of `padded` or more do not fit into the Boolector shift.
+        let high = Node::Slice(amount, amount_width - 1, log2).into_ref(solver);
+        let overflow = Node::RedOr(&high).into_ref(solver);
+
+        let fill = if signed {
+            let max_amount = BitVecValue::from_u64(log2, u64::from(padded - 1)).to_node(solver);
+            let fill = f(&operand, &max_amount).into_ref(solver);
+            Node::Slice(&fill, width - 1, 0).into_ref(solver)
+        } else {
+            BitVecValue::from_u64(width, 0).to_node(solver)
+        };
+
+        Node::Cond(&overflow, &fill, &shifted).into_ref(solver)
+    }
+
+    /// Rotate by an arbitrary amount, reduced modulo the width.
+    fn rotate(&self, amount: &NodeRef<'a>, left: bool) -> NodeRef<'a> {
+        let solver = self.solver;
+        let width = self.width();
+
+        if width == 1 {
+            return self.clone();
+        }
+
+        // Compute `amount mod width` in a sort wide enough to hold `width`.
+        let amount_width = amount.width();
+        let mod_width = amount_width.max(32 - width.leading_zeros());
+
+        let amount = if mod_width > amount_width {
+            Node::UExt(amount, mod_width - amount_width).into_ref(solver)
+        } else {
+            amount.clone()
+        };
+
+        let modulus = BitVecValue::from_u64(mod_width, u64::from(width)).to_node(solver);
+        let forward = Node::URem(&amount, &modulus).into_ref(solver);
+        let backward = Node::Sub(&modulus, &forward).into_ref(solver);
+
+        let (left_amount, right_amount) = if left {
+            (forward, backward)
+        } else {
+            (backward, forward)
+        };
+
+        // A shift by `width` yields zero, so a rotation by zero works as well.
+        let high = self.shift_left(&left_amount);
+        let low = self.shift_right_logical(&right_amount);
+
+        Node::Or(&high, &low).into_ref(solver)
+    }
+}
@@ -0,0 +1,108 @@
+//! Helpers for formatting nodes and sorts in SMT-LIB 2 syntax.
+
+use std::fmt;
+
+use boolector_sys as ffi;
+
+use crate::cfile;
+
+/// Number of bytes of a dump kept for display.
+pub(crate) const DISPLAY_LIMIT: usize = 4096;
+
+/// Dump a node with `boolector_dump_smt2_node`, keeping at most
+/// `DISPLAY_LIMIT` bytes; cut output ends in `...`.
+///
+/// Boolector always writes the whole term, so the time and temporary space
+/// this takes grow with the size of the term, not of the kept output.  Fails
+/// if no temporary file can be created.
+pub(crate) unsafe fn dump_node(btor_ptr: *mut ffi::Btor, node_ptr: *mut ffi::BoolectorNode) -> Result<String, fmt::Error> {
+    let file = libc::tmpfile();
+
+    if file.is_null() {
+        return Err(fmt::Error);
+    }
+
+    ffi::boolector_dump_smt2_node(btor_ptr, file as *mut ffi::FILE, node_ptr);
+
+    let (mut text, cut) = cfile::read_prefix_and_close(file, DISPLAY_LIMIT);
+    text.truncate(text.trim_end().len());
+
+    if cut {
+        text.push_str("...");
+    }
+
+    Ok(text)
+}
+
+/// Split a `(declare-fun name (domain) codomain)` command into the name and
+/// everything after it.
+///
+/// Returns `None` unless `text` is exactly one such command on one line.
+pub(crate) fn declaration(text: &str) -> Option<(&str, &str)> {
+    if text.contains('\n') || !is_single_term(text) {
+        return None;
+    }
+
+    let rest = text.strip_prefix("(declare-fun ")?.strip_suffix(')')?;
+
+    // Quoted symbols may contain spaces.
+    let end = match rest.strip_prefix('|') {
+        Some(quoted) => quoted.find('|')? + 2,
+        None => rest.find(' ')?,
+    };
+
+    Some((&rest[..end], rest[end..].trim_start()))
+}
+
+/// Return the widths of the domain sorts in the part of a declaration after
+/// the name.
+pub(crate) fn domain_widths(signature: &str) -> Vec<u32> {
+    let (domain, _) = split_domain(signature).expect("unexpected declaration");
+
+    domain
+        .split("(_ BitVec ")
+        .skip(1)
+        .map(|item| item.split(')').next().and_then(|width| width.parse().ok()).expect("unexpected domain sort"))
+        .collect()
+}
+
+/// Return whether `text` is one parenthesized term whose parentheses balance,
+/// skipping quoted symbols.
+fn is_single_term(text: &str) -> bool {
+    let mut depth = 0;
+    let mut quoted = false;
+
+    for (pos, c) in text.char_indices() {
+        match c {
+            '|' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            _ => continue,
+        }
+
+        if depth == 0 && !quoted {
+            return pos + 1 == text.len();
+        }
+    }
+
+    false
+}
+
+/// Split `(d1 d2) c` into `d1 d2` and `c`.
+fn split_domain(signature: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+
+    for (pos, c) in signature.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => continue,
+        }
+
+        if depth == 0 {
+            return Some((&signature[1..pos], signature[pos + 1..].trim_start()));
+        }
+    }
+
+    None
+}
@@ -1,6 +1,11 @@
 use crate::Model;
 
 /// Result of a `solve` operation.
+///
+/// `Unknown` was added for terminated calls; matches outside this crate need
+/// a wildcard arm, so that further variants can be added without breaking
+/// them again.
+#[non_exhaustive]
 pub enum SolveResult<'a> {
     /// The formula is satisfiable.  Depending on `Builder` configuration, a
     /// model may be provided.
@@ -8,6 +13,10 @@ pub enum SolveResult<'a> {
 
     /// The formula is unsatisfiable.
     Unsat,
+
+    /// The solver was terminated before reaching a result, see
+    /// [Solver::set_termination](struct.Solver.html#method.set_termination).
+    Unknown,
 }
 
 impl<'a> SolveResult<'a> {
@@ -15,7 +24,7 @@ impl<'a> SolveResult<'a> {
     pub fn is_sat(&self) -> bool {
         match self {
             SolveResult::Sat(..) => true,
-            SolveResult::Unsat => false,
+            SolveResult::Unsat | SolveResult::Unknown => false,
         }
     }
 }
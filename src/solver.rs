@@ -1,40 +1,203 @@
-use std::{mem, ptr};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::time::Instant;
+use std::{iter, mem, ptr, thread};
 
 use boolector_sys as ffi;
 
-use crate::{Model, Node, NodeRef, SolveResult, Sort, SortRef};
+use crate::api_trace::ApiTrace;
+use crate::cfile;
+use crate::dag::Dag;
+use crate::termination::Termination;
+use crate::{eval, Assignment, BitVecValue, CheckedResult, LeakCheck, Model, ModelError, Node, NodeKind, NodeRef, SolveResult, SolveTimes, Sort, SortRef, Statistics, Visitor};
 
 /// Solver instance.
+///
+/// A solver instance can be moved to another thread together with everything
+/// it owns, but node and sort references borrow it and must stay behind.  To
+/// use one instance from several threads, see
+/// [SharedSolver](struct.SharedSolver.html).
+///
+/// ```
+/// let solver = boolector::Builder::new().finish();
+///
+/// let handle = std::thread::spawn(move || {
+///     let b8 = solver.sort(boolector::Sort::BitVec(8));
+///     let x = b8.var(Some("x"));
+///     let y = b8.var(Some("y"));
+///     solver.assert(&solver.node(boolector::Node::Eq(&x, &y)));
+///     solver.solve().is_sat()
+/// });
+///
+/// assert!(handle.join().unwrap());
+/// ```
 pub struct Solver {
     btor_ptr: *mut ffi::Btor,
+    leak_check: LeakCheck,
+    escaped: RefCell<HashMap<EscapedKey, Escaped>>,
+    solve_calls: Cell<u64>,
+    solve_times: Cell<Option<SolveTimes>>,
+    dag: RefCell<Option<Dag>>,
+    assertions: RefCell<Option<Vec<*mut ffi::BoolectorNode>>>,
+    check_model: bool,
+    scopes: RefCell<Vec<usize>>,
+    vars: RefCell<Vec<*mut ffi::BoolectorNode>>,
+    // Number of generated variable symbols, once scopes have been used.
+    unnamed_vars: Cell<Option<u32>>,
+    // Highest node id handed out, and its value at the last `solve` call.
+    max_node_id: Cell<i32>,
+    solved_node_id: Cell<i32>,
+    termination: OnceCell<Box<Termination>>,
+    api_trace: Option<ApiTrace>,
+}
+
+/// Raw value of a reference that left the safe API through `into_ffi`.
+#[derive(Eq, Hash, PartialEq)]
+enum EscapedKey {
+    Node(*mut ffi::BoolectorNode),
+    Sort(ffi::BoolectorSort),
+}
+
+/// References that left with the same raw value; nodes keep their id and
+/// symbol for the leak report.
+struct Escaped {
+    refs: u32,
+    node: Option<(i32, Option<String>)>,
 }
 
 impl Solver {
     /// Create a node instance.
-    pub fn node<'a>(&'a self, node: Node<'a>) -> NodeRef<'a> {
+    pub fn node<'a>(&'a self, node: Node<'a, '_>) -> NodeRef<'a> {
         node.into_ref(self)
     }
 
     /// Create a sort instance.
-    pub fn sort<'a>(&'a self, sort: Sort<'a>) -> SortRef<'a> {
+    pub fn sort<'a>(&'a self, sort: Sort<'a, '_>) -> SortRef<'a> {
         sort.into_ref(self)
     }
 
     /// Assert an expression.
-    pub fn assert<'a>(&'a self, expr: &'a NodeRef<'a>) {
+    pub fn assert(&self, expr: &NodeRef<'_>) {
         assert_eq!(self.btor_ptr, expr.solver().btor_ptr);
 
         unsafe {
             ffi::boolector_assert(self.btor_ptr, expr.node_ptr());
         }
+
+        if let Some(assertions) = self.assertions.borrow_mut().as_mut() {
+            assertions.push(unsafe { ffi::boolector_copy(self.btor_ptr, expr.node_ptr()) });
+        }
+    }
+
+    /// Assume an expression for the next `solve` call only.
+    ///
+    /// Requires incremental solving, see
+    /// [Builder::incremental](struct.Builder.html#method.incremental).
+    pub fn assume(&self, expr: &NodeRef<'_>) {
+        assert_eq!(self.btor_ptr, expr.solver().btor_ptr);
+
+        unsafe {
+            ffi::boolector_assume(self.btor_ptr, expr.node_ptr());
+        }
+    }
+
+    /// Discard all assumptions that have not been used by a `solve` call yet.
+    pub fn reset_assumptions(&self) {
+        unsafe {
+            ffi::boolector_reset_assumptions(self.btor_ptr);
+        }
+    }
+
+    /// Open `levels` new assertion scopes.
+    ///
+    /// Requires incremental solving, see
+    /// [Builder::incremental](struct.Builder.html#method.incremental).
+    pub fn push(&self, levels: u32) {
+        unsafe {
+            ffi::boolector_push(self.btor_ptr, levels);
+        }
+
+        if self.unnamed_vars.get().is_none() {
+            self.unnamed_vars.set(Some(0));
+        }
+
+        let asserted = self.assertions.borrow().as_ref().map_or(0, Vec::len);
+        self.scopes.borrow_mut().extend(iter::repeat_n(asserted, levels as usize));
+    }
+
+    /// Close `levels` assertion scopes, discarding everything asserted in them.
+    ///
+    /// Panics if fewer scopes are open.
+    pub fn pop(&self, levels: u32) {
+        let mut scopes = self.scopes.borrow_mut();
+        assert!(levels as usize <= scopes.len(), "cannot pop more scopes than are open");
+
+        if levels == 0 {
+            return;
+        }
+
+        unsafe {
+            ffi::boolector_pop(self.btor_ptr, levels);
+        }
+
+        let depth = scopes.len() - levels as usize;
+        let asserted = scopes[depth];
+        scopes.truncate(depth);
+
+        if let Some(assertions) = self.assertions.borrow_mut().as_mut() {
+            for node_ptr in assertions.drain(asserted..) {
+                unsafe {
+                    ffi::boolector_release(self.btor_ptr, node_ptr);
+                }
+            }
+        }
+    }
+
+    /// Return every variable, array and uninterpreted function created with
+    /// [SortRef::var](struct.SortRef.html#method.var), in order of creation.
+    pub fn vars(&self) -> Vec<NodeRef<'_>> {
+        self.vars
+            .borrow()
+            .iter()
+            .map(|&node_ptr| unsafe {
+                NodeRef::wrap(self, ffi::boolector_copy(self.btor_ptr, node_ptr))
+            })
+            .collect()
+    }
+
+    /// Return a fresh symbol for a variable created without one, if needed.
+    ///
+    /// Once scopes have been used, Boolector crashes on variables without a
+    /// symbol.
+    pub(crate) fn unnamed_symbol(&self) -> Option<CString> {
+        let count = self.unnamed_vars.get()?;
+        self.unnamed_vars.set(Some(count + 1));
+
+        Some(CString::new(format!("unnamed!{}", count)).unwrap())
+    }
+
+    /// Return the number of open assertion scopes.
+    pub fn scopes(&self) -> u32 {
+        self.scopes.borrow().len() as u32
     }
 
     /// Solve the formula.
     pub fn solve(&self) -> SolveResult {
+        let start = Instant::now();
+        self.solved_node_id.set(self.max_node_id.get());
+
         let result = unsafe {
             ffi::boolector_sat(self.btor_ptr)
         };
 
+        self.solve_calls.set(self.solve_calls.get() + 1);
+
+        if let Some(mut times) = self.solve_times.get() {
+            times.record(start.elapsed());
+            self.solve_times.set(Some(times));
+        }
+
         if result == ffi::BtorSolverResult_BTOR_RESULT_SAT as i32 {
             let model_was_generated = unsafe {
                 ffi::boolector_get_opt(
@@ -50,21 +213,262 @@ impl Solver {
             }
         } else if result == ffi::BtorSolverResult_BTOR_RESULT_UNSAT as i32 {
             SolveResult::Unsat
+        } else if result == ffi::BtorSolverResult_BTOR_RESULT_UNKNOWN as i32 {
+            SolveResult::Unknown
         } else {
             panic!("unexpected return value from boolector_sat()");
         }
     }
 
+    /// Install a callback that Boolector polls while solving; once it returns
+    /// `true`, `solve` stops and returns
+    /// [SolveResult::Unknown](enum.SolveResult.html#variant.Unknown).
+    ///
+    /// The callback may be called from `solve` many times per second, so it
+    /// should be cheap.  A panicking callback terminates the solver.
+    pub fn set_termination<F: FnMut() -> bool + Send + 'static>(&self, callback: F) {
+        self.termination().set_callback(Some(Box::new(callback)));
+    }
+
+    /// Remove the callback installed with
+    /// [set_termination](#method.set_termination).
+    pub fn clear_termination(&self) {
+        if let Some(termination) = self.termination.get() {
+            termination.set_callback(None);
+        }
+    }
+
+    /// Solve the formula and check the model against every asserted
+    /// expression, see
+    /// [Builder::check_model](struct.Builder.html#method.check_model).
+    ///
+    /// Each assertion is evaluated in Rust with [eval](fn.eval.html) under the
+    /// values the model gives to its variables.  Assertions that involve arrays
+    /// are not checked.
+    ///
+    /// Panics if model checking is not enabled.
+    ///
+    /// ```
+    /// let solver = boolector::Builder::new().check_model(true).finish();
+    /// let b8 = solver.sort(boolector::Sort::BitVec(8));
+    /// let x = b8.var(Some("x"));
+    ///
+    /// // Name the assertion so that a violation can be traced back to it.
+    /// let wraps = boolector::Unsigned(&x + 1).lt(&boolector::Unsigned(x.clone()));
+    /// wraps.set_symbol("increment wraps around");
+    /// solver.assert(&wraps);
+    ///
+    /// match solver.solve_checked() {
+    ///     Ok(checked) => assert!(checked.result.is_sat() && checked.unchecked.is_empty()),
+    ///     Err(e) => panic!("{}", e),
+    /// }
+    /// ```
+    pub fn solve_checked(&self) -> Result<CheckedResult<'_>, ModelError> {
+        assert!(self.check_model, "model checking is not enabled");
+
+        let result = self.solve();
+        let mut unchecked = Vec::new();
+
+        if let SolveResult::Sat(Some(model)) = &result {
+            let assertions = self.assertions.borrow();
+
+            for (index, &node_ptr) in assertions.iter().flatten().enumerate() {
+                let expr = unsafe {
+                    NodeRef::wrap(self, ffi::boolector_copy(self.btor_ptr, node_ptr))
+                };
+
+                match holds(model, &expr) {
+                    Some(true) => {},
+                    Some(false) => {
+                        return Err(ModelError {
+                            index,
+                            id: expr.id(),
+                            symbol: expr.symbol(),
+                        });
+                    },
+                    None => unchecked.push(index),
+                }
+            }
+        }
+
+        Ok(CheckedResult { result, unchecked })
+    }
+
+    /// Return a snapshot of solver statistics.
+    ///
+    /// Boolector prints its statistics only to standard output, so this
+    /// briefly redirects standard output of the whole process: output that
+    /// other threads write meanwhile outside of Rust's `print!` may end up in
+    /// the statistics or be lost.  If the redirection fails, the fields
+    /// reported by Boolector are left empty.
+    pub fn statistics(&self) -> Statistics {
+        let verbosity = ffi::BtorOption_BTOR_OPT_VERBOSITY;
+
+        let output = cfile::capture_stdout(|| unsafe {
+            let saved = ffi::boolector_get_opt(self.btor_ptr, verbosity);
+
+            // Boolector only prints statistics with non-zero verbosity.
+            ffi::boolector_set_opt(self.btor_ptr, verbosity, saved.max(1));
+            ffi::boolector_print_stats(self.btor_ptr);
+            ffi::boolector_set_opt(self.btor_ptr, verbosity, saved);
+        });
+
+        let mut stats = Statistics::parse(&output.unwrap_or_default());
+
+        stats.solve_calls = self.solve_calls.get();
+        stats.solve_times = self.solve_times.get();
+        stats
+    }
+
+    /// Return the asserted formula in SMT-LIB 2 format.
+    ///
+    /// Boolector does not support dumping with pending assumptions.
+    pub fn dump_smt2(&self) -> String {
+        unsafe {
+            let file = libc::tmpfile();
+            assert!(!file.is_null(), "cannot create temporary file");
+
+            ffi::boolector_dump_smt2(self.btor_ptr, file as *mut ffi::FILE);
+            cfile::read_and_close(file)
+        }
+    }
+
+    /// Parse assertions in SMT-LIB 2 format, such as
+    /// [dump_smt2](#method.dump_smt2) returns, into this instance.
+    ///
+    /// Commands other than declarations and assertions are dropped, so that
+    /// parsing never solves.
+    pub(crate) fn parse_smt2(&self, input: &str) -> Result<(), String> {
+        let input: String = input
+            .lines()
+            .filter(|line| !matches!(line.trim(), "(check-sat)" | "(exit)"))
+            .flat_map(|line| [line, "\n"])
+            .collect();
+
+        let name = CString::new("<dump>").unwrap();
+
+        unsafe {
+            let infile = cfile::open_str(&input);
+            let outfile = libc::tmpfile();
+            assert!(!infile.is_null() && !outfile.is_null(), "cannot create temporary file");
+
+            let mut error_ptr = ptr::null_mut();
+            let mut status = 0;
+
+            ffi::boolector_parse_smt2(
+                self.btor_ptr,
+                infile as *mut ffi::FILE,
+                name.as_ptr(),
+                outfile as *mut ffi::FILE,
+                &mut error_ptr,
+                &mut status,
+            );
+
+            libc::fclose(infile);
+            libc::fclose(outfile);
+
+            if error_ptr.is_null() {
+                Ok(())
+            } else {
+                Err(CStr::from_ptr(error_ptr).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Return the number of node and sort references that are still alive.
+    pub fn outstanding_refs(&self) -> u32 {
+        let refs = unsafe {
+            ffi::boolector_get_refs(self.btor_ptr)
+        };
+
+        refs - self.held_refs()
+    }
+
+    /// Set leak check mode, see
+    /// [Builder::leak_check](struct.Builder.html#method.leak_check).
+    pub fn set_leak_check(&mut self, mode: LeakCheck) {
+        self.leak_check = mode;
+
+        if mode == LeakCheck::Disabled {
+            self.escaped.borrow_mut().clear();
+        }
+    }
+
+    /// Enable or disable recording of solve times, see
+    /// [Builder::record_solve_times](struct.Builder.html#method.record_solve_times).
+    pub(crate) fn set_record_solve_times(&mut self, enable: bool) {
+        *self.solve_times.get_mut() = if enable { Some(SolveTimes::default()) } else { None };
+    }
+
+    /// Enable or disable recording of expression DAGs and asserted
+    /// expressions, see
+    /// [Builder::record_dag](struct.Builder.html#method.record_dag).
+    pub(crate) fn set_record_dag(&mut self, enable: bool) {
+        if let Some(dag) = self.dag.get_mut() {
+            unsafe {
+                dag.release(self.btor_ptr);
+            }
+        }
+
+        if let Some(assertions) = self.assertions.get_mut().take() {
+            for node_ptr in assertions {
+                unsafe {
+                    ffi::boolector_release(self.btor_ptr, node_ptr);
+                }
+            }
+        }
+
+        *self.dag.get_mut() = if enable { Some(Dag::new()) } else { None };
+        *self.assertions.get_mut() = if enable { Some(Vec::new()) } else { None };
+    }
+
+    /// Enable or disable `solve_checked`, see
+    /// [Builder::check_model](struct.Builder.html#method.check_model).
+    pub(crate) fn set_check_model(&mut self, enable: bool) {
+        self.check_model = enable;
+    }
+
+    /// Take ownership of the API trace attached to this instance, so that it is
+    /// closed after the instance is deleted.
+    pub(crate) fn set_api_trace(&mut self, trace: Option<ApiTrace>) {
+        self.api_trace = trace;
+    }
+
     /// Construct a solver instance from raw `Btor` pointer.
     pub unsafe fn from_ffi(btor_ptr: *mut ffi::Btor) -> Self {
         Solver {
             btor_ptr,
+            leak_check: LeakCheck::Disabled,
+            escaped: RefCell::new(HashMap::new()),
+            solve_calls: Cell::new(0),
+            solve_times: Cell::new(None),
+            dag: RefCell::new(None),
+            assertions: RefCell::new(None),
+            check_model: false,
+            scopes: RefCell::new(Vec::new()),
+            vars: RefCell::new(Vec::new()),
+            unnamed_vars: Cell::new(None),
+            max_node_id: Cell::new(0),
+            solved_node_id: Cell::new(0),
+            termination: OnceCell::new(),
+            api_trace: None,
         }
     }
 
     /// Consume the solver instance and return the underlying raw `Btor`
     /// pointer.
+    ///
+    /// If an API trace is attached to the instance, its file is never closed.
     pub fn into_ffi(mut self) -> *mut ffi::Btor {
+        self.release_held_refs();
+
+        if self.termination.get().is_some() {
+            unsafe {
+                ffi::boolector_set_term(self.btor_ptr, None, ptr::null_mut());
+            }
+        }
+
+        mem::forget(self.api_trace.take());
         mem::replace(&mut self.btor_ptr, ptr::null_mut())
     }
 
@@ -74,14 +478,255 @@ impl Solver {
     }
 }
 
+// A `Btor` instance is not bound to the thread that created it, and the safe
+// API cannot reach it through a shared reference from another thread.
+unsafe impl Send for Solver {}
+
+impl Solver {
+    /// Remember a node reference that was turned into a raw pointer.
+    pub(crate) fn escape_node(&self, node_ptr: *mut ffi::BoolectorNode) {
+        if self.leak_check == LeakCheck::Disabled || node_ptr.is_null() {
+            return;
+        }
+
+        let mut escaped = self.escaped.borrow_mut();
+
+        let entry = escaped.entry(EscapedKey::Node(node_ptr)).or_insert_with(|| unsafe {
+            let symbol_ptr = ffi::boolector_get_symbol(self.btor_ptr, node_ptr);
+            let symbol = if symbol_ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(symbol_ptr).to_string_lossy().into_owned())
+            };
+
+            Escaped {
+                refs: 0,
+                node: Some((ffi::boolector_get_node_id(self.btor_ptr, node_ptr), symbol)),
+            }
+        });
+
+        entry.refs += 1;
+    }
+
+    /// Forget a raw node pointer that was turned back into a node reference.
+    pub(crate) fn reclaim_node(&self, node_ptr: *mut ffi::BoolectorNode) {
+        self.reclaim(EscapedKey::Node(node_ptr));
+    }
+
+    /// Remember a sort reference that was turned into a raw value.
+    pub(crate) fn escape_sort(&self, sort_ptr: ffi::BoolectorSort) {
+        if self.leak_check != LeakCheck::Disabled && !sort_ptr.is_null() {
+            let mut escaped = self.escaped.borrow_mut();
+            escaped.entry(EscapedKey::Sort(sort_ptr)).or_insert(Escaped { refs: 0, node: None }).refs += 1;
+        }
+    }
+
+    /// Forget a raw sort value that was turned back into a sort reference.
+    pub(crate) fn reclaim_sort(&self, sort_ptr: ffi::BoolectorSort) {
+        self.reclaim(EscapedKey::Sort(sort_ptr));
+    }
+
+    fn reclaim(&self, key: EscapedKey) {
+        if self.leak_check == LeakCheck::Disabled {
+            return;
+        }
+
+        let mut escaped = self.escaped.borrow_mut();
+
+        if let Some(entry) = escaped.get_mut(&key) {
+            entry.refs -= 1;
+
+            if entry.refs == 0 {
+                escaped.remove(&key);
+            }
+        }
+    }
+
+    /// Record the description a node was created from, if recording is
+    /// enabled.
+    pub(crate) fn record_node(&self, node: &NodeRef<'_>, desc: &Node<'_, '_>) {
+        if let Some(dag) = self.dag.borrow_mut().as_mut() {
+            dag.record(node, desc);
+        }
+    }
+
+    /// Return whether expression DAGs are recorded.
+    pub(crate) fn records_dag(&self) -> bool {
+        self.dag.borrow().is_some()
+    }
+
+    /// Return the recorded description of a node.
+    pub(crate) fn node_kind<'a>(&'a self, node: &NodeRef<'_>) -> Option<NodeKind<'a>> {
+        self.dag.borrow().as_ref().and_then(|dag| dag.kind(self, node))
+    }
+
+    /// Return the kept assertions, or `None` unless DAG recording is enabled.
+    pub(crate) fn assertions(&self) -> Option<Vec<NodeRef<'_>>> {
+        self.assertions.borrow().as_ref().map(|assertions| {
+            assertions
+                .iter()
+                .map(|&node_ptr| unsafe { NodeRef::wrap(self, ffi::boolector_copy(self.btor_ptr, node_ptr)) })
+                .collect()
+        })
+    }
+
+    /// Note the id of a node that entered the safe API.
+    pub(crate) fn observe_node(&self, node_ptr: *mut ffi::BoolectorNode) {
+        let id = unsafe {
+            ffi::boolector_get_node_id(self.btor_ptr, node_ptr)
+        };
+
+        self.max_node_id.set(self.max_node_id.get().max(id.abs()));
+    }
+
+    /// Return whether a node already existed at the last `solve` call.
+    pub(crate) fn existed_at_solve(&self, node: &NodeRef<'_>) -> bool {
+        node.id().abs() <= self.solved_node_id.get()
+    }
+
+    /// Remember a variable created with `SortRef::var`.
+    pub(crate) fn declare_var(&self, var: &NodeRef<'_>) {
+        let node_ptr = unsafe {
+            ffi::boolector_copy(self.btor_ptr, var.node_ptr())
+        };
+
+        self.vars.borrow_mut().push(node_ptr);
+    }
+
+    /// Stop tracking the variables declared so far.
+    pub(crate) fn forget_vars(&self) {
+        for node_ptr in self.vars.borrow_mut().drain(..) {
+            unsafe {
+                ffi::boolector_release(self.btor_ptr, node_ptr);
+            }
+        }
+    }
+
+    /// Return the termination state, registering it on first use.
+    pub(crate) fn termination(&self) -> &Termination {
+        self.termination.get_or_init(|| {
+            let termination = Termination::new();
+
+            unsafe {
+                termination.attach(self.btor_ptr);
+            }
+
+            termination
+        })
+    }
+
+    /// Return the number of references held by the instance itself.
+    fn held_refs(&self) -> u32 {
+        let dag_refs = self.dag.borrow().as_ref().map_or(0, Dag::refs);
+        let assertion_refs = self.assertions.borrow().as_ref().map_or(0, Vec::len);
+        let var_refs = self.vars.borrow().len();
+
+        dag_refs + assertion_refs as u32 + var_refs as u32
+    }
+
+    /// Release the references held by the instance itself.
+    fn release_held_refs(&mut self) {
+        self.set_record_dag(false);
+        self.forget_vars();
+    }
+
+    /// Describe leaked references, if there are any.
+    fn leak_report(&self) -> Option<String> {
+        let refs = self.outstanding_refs();
+
+        if refs == 0 {
+            return None;
+        }
+
+        let mut report = format!("boolector: {} references leaked", refs);
+
+        let mut escaped: Vec<_> = self.escaped
+            .borrow()
+            .values()
+            .filter_map(|escaped| escaped.node.clone())
+            .collect();
+
+        escaped.sort();
+
+        let nodes: Vec<_> = escaped
+            .into_iter()
+            .map(|(id, symbol)| match symbol {
+                Some(symbol) => format!("{} (node {})", symbol, id),
+                None => format!("node {}", id),
+            })
+            .collect();
+
+        if !nodes.is_empty() {
+            report.push_str(": ");
+            report.push_str(&nodes.join(", "));
+        }
+
+        Some(report)
+    }
+}
+
 impl Drop for Solver {
     fn drop(&mut self) {
         if !self.btor_ptr.is_null() {
-            println!("refs: {}", unsafe { ffi::boolector_get_refs(self.btor_ptr) });
+            self.release_held_refs();
+
+            let report = match self.leak_check {
+                LeakCheck::Disabled => None,
+                LeakCheck::Report | LeakCheck::Panic => self.leak_report(),
+            };
 
             unsafe {
+                if report.is_some() {
+                    ffi::boolector_release_all(self.btor_ptr);
+                }
+
                 ffi::boolector_delete(self.btor_ptr);
             }
+
+            if let Some(report) = report {
+                match self.leak_check {
+                    LeakCheck::Panic if !thread::panicking() => panic!("{}", report),
+                    _ => eprintln!("{}", report),
+                }
+            }
         }
     }
 }
+
+/// Check whether an asserted expression evaluates to true under a model, or
+/// return `None` if it reads arrays or applies functions.
+fn holds(model: &Model<'_>, expr: &NodeRef<'_>) -> Option<bool> {
+    struct Leaves<'a>(Vec<NodeRef<'a>>);
+
+    impl<'a> Visitor<'a> for Leaves<'a> {
+        fn visit(&mut self, node: &NodeRef<'a>, kind: Option<&NodeKind<'a>>) {
+            if kind.is_none() {
+                self.0.push(node.clone());
+            }
+        }
+    }
+
+    let mut leaves = Leaves(Vec::new());
+    expr.walk(&mut leaves);
+
+    let btor_ptr = model.solver().btor_ptr();
+    let mut assignment = Assignment::new();
+
+    for leaf in &leaves.0 {
+        unsafe {
+            if ffi::boolector_is_const(btor_ptr, leaf.node_ptr()) {
+                continue;
+            }
+
+            if ffi::boolector_is_array(btor_ptr, leaf.node_ptr()) || ffi::boolector_is_fun(btor_ptr, leaf.node_ptr()) {
+                return None;
+            }
+        }
+
+        let value = BitVecValue::from_bits(model.bit_vec(leaf).to_str());
+        assignment.set(leaf, value);
+    }
+
+    // Anything but a single bit cannot be checked either.
+    eval(expr, &assignment).as_bit_vec().and_then(BitVecValue::to_bool)
+}
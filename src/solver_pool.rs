@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::{Builder, Config, GenerateModel, Solver};
+
+/// Pool of reusable incremental solver instances, keyed by configuration.
+///
+/// Creating a Boolector instance costs far more than a small query, so the
+/// pool keeps instances that were handed back and reuses them.  Each
+/// instance is handed out inside a fresh assertion scope, which is closed
+/// (together with any scopes opened by the caller) when it is returned.
+/// Handles can be cloned and shared between threads.
+///
+/// ```
+/// use std::thread;
+///
+/// use boolector::{Config, SolverPool, Sort};
+///
+/// let pool = SolverPool::new(4);
+///
+/// let workers: Vec<_> = (0..8)
+///     .map(|i| {
+///         let pool = pool.clone();
+///
+///         thread::spawn(move || {
+///             for j in 0..100 {
+///                 let solver = pool.get(&Config::default());
+///                 let b8 = solver.sort(Sort::BitVec(8));
+///                 let x = b8.var(None);
+///                 let k = i * j % 256;
+///                 solver.assert(&solver.node(boolector::Node::Eq(&x, &(&x + k))));
+///                 assert_eq!(solver.solve().is_sat(), k == 0);
+///             }
+///         })
+///     })
+///     .collect();
+///
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+///
+/// assert!(pool.idle(&Config::default()) <= 4);
+/// ```
+#[derive(Clone)]
+pub struct SolverPool {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    idle: Mutex<HashMap<Config, Vec<Solver>>>,
+    max_idle: usize,
+    generate_model: Option<GenerateModel>,
+}
+
+impl SolverPool {
+    /// Create a pool that keeps at most `max_idle` idle instances per
+    /// configuration, without model generation.
+    pub fn new(max_idle: usize) -> Self {
+        Self::build(max_idle, None)
+    }
+
+    /// Create a pool like [new](#method.new) whose instances generate models.
+    pub fn with_models(max_idle: usize, mode: GenerateModel) -> Self {
+        Self::build(max_idle, Some(mode))
+    }
+
+    fn build(max_idle: usize, generate_model: Option<GenerateModel>) -> Self {
+        SolverPool {
+            inner: Arc::new(Inner {
+                idle: Mutex::new(HashMap::new()),
+                max_idle,
+                generate_model,
+            }),
+        }
+    }
+
+    /// Take an idle instance with the given configuration, or create one if
+    /// there is none.
+    pub fn get(&self, config: &Config) -> PooledSolver {
+        let idle = self.lock().get_mut(config).and_then(Vec::pop);
+
+        let solver = idle.unwrap_or_else(|| {
+            let builder = Builder::new().config(config).incremental(true);
+
+            match self.inner.generate_model {
+                Some(mode) => builder.generate_model(mode).finish(),
+                None => builder.finish(),
+            }
+        });
+
+        solver.push(1);
+
+        PooledSolver {
+            solver: Some(solver),
+            config: *config,
+            pool: self.clone(),
+        }
+    }
+
+    /// Return the number of idle instances with the given configuration.
+    pub fn idle(&self, config: &Config) -> usize {
+        self.lock().get(config).map_or(0, Vec::len)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Config, Vec<Solver>>> {
+        self.inner.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn put(&self, config: Config, solver: Solver) {
+        solver.pop(solver.scopes());
+        solver.reset_assumptions();
+        solver.clear_termination();
+        solver.forget_vars();
+
+        // References that escaped through `into_ffi` would keep parts of the
+        // previous query alive; such instances are not reused.
+        if solver.outstanding_refs() != 0 {
+            return;
+        }
+
+        let mut idle = self.lock();
+        let instances = idle.entry(config).or_default();
+
+        if instances.len() < self.inner.max_idle {
+            instances.push(solver);
+        }
+    }
+}
+
+/// Solver instance borrowed from a [SolverPool](struct.SolverPool.html); it
+/// goes back to the pool when dropped.
+pub struct PooledSolver {
+    solver: Option<Solver>,
+    config: Config,
+    pool: SolverPool,
+}
+
+impl PooledSolver {
+    /// Return the configuration of this instance.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+}
+
+impl Deref for PooledSolver {
+    type Target = Solver;
+
+    fn deref(&self) -> &Solver {
+        self.solver.as_ref().unwrap()
+    }
+}
+
+impl Drop for PooledSolver {
+    fn drop(&mut self) {
+        if let Some(solver) = self.solver.take() {
+            self.pool.put(self.config, solver);
+        }
+    }
+}
@@ -1,39 +1,44 @@
 use boolector_sys as ffi;
 
+use crate::sort_ref::SortShape;
 use crate::{Solver, SortRef};
 
 /// Sort description.
 ///
 /// For references to sort instances, see [SortRef](struct.SortRef.html).
-pub enum Sort<'a> {
+pub enum Sort<'a, 'b> {
     /// Array with bit vector indexes and bit vector values.
-    Array(&'a SortRef<'a>, &'a SortRef<'a>),
+    Array(&'b SortRef<'a>, &'b SortRef<'a>),
 
     /// Bit vector of given length.
     BitVec(u32),
 
     /// Function with given inputs and a single output.
-    Fun(&'a [&'a SortRef<'a>], &'a SortRef<'a>),
+    Fun(&'b [&'b SortRef<'a>], &'b SortRef<'a>),
 }
 
-impl<'a> Sort<'a> {
+impl<'a, 'b> Sort<'a, 'b> {
     /// Create a sort reference.
     pub fn into_ref(self, solver: &'a Solver) -> SortRef<'a> {
         match self {
             Sort::Array(index_sort, value_sort) => unsafe {
-                SortRef::from_ffi(
+                let sort_ptr = ffi::boolector_array_sort(
+                    solver.btor_ptr(),
+                    index_sort.sort_ptr(),
+                    value_sort.sort_ptr(),
+                );
+
+                SortRef::wrap_shaped(
                     solver,
-                    ffi::boolector_array_sort(
-                        solver.btor_ptr(),
-                        index_sort.sort_ptr(),
-                        value_sort.sort_ptr(),
-                    ),
+                    sort_ptr,
+                    SortShape::Array(index_sort.width(), value_sort.width()),
                 )
             },
             Sort::BitVec(bits) => unsafe {
-                SortRef::from_ffi(
+                SortRef::wrap_shaped(
                     solver,
                     ffi::boolector_bitvec_sort(solver.btor_ptr(), bits),
+                    SortShape::BitVec(bits),
                 )
             },
             Sort::Fun(domain, codomain) => unsafe {
@@ -42,14 +47,17 @@ impl<'a> Sort<'a> {
                     .map(|sort| sort.sort_ptr())
                     .collect();
 
-                SortRef::from_ffi(
+                let sort_ptr = ffi::boolector_fun_sort(
+                    solver.btor_ptr(),
+                    domain_ptrs.as_mut_ptr(),
+                    domain_ptrs.len() as u32,
+                    codomain.sort_ptr(),
+                );
+
+                SortRef::wrap_shaped(
                     solver,
-                    ffi::boolector_fun_sort(
-                        solver.btor_ptr(),
-                        domain_ptrs.as_mut_ptr(),
-                        domain_ptrs.len() as u32,
-                        codomain.sort_ptr(),
-                    ),
+                    sort_ptr,
+                    SortShape::Fun(domain.iter().map(|sort| sort.width()).collect(), codomain.width()),
                 )
             },
         }
@@ -1,19 +1,36 @@
+use std::cell::OnceCell;
 use std::ffi::CString;
-use std::{mem, ptr};
+use std::os::raw::c_char;
+use std::{fmt, mem, ptr};
 
 use boolector_sys as ffi;
 
-use crate::{NodeRef, Solver};
+use crate::{smt2, NodeRef, Solver, Sort};
+
+/// Symbol of the temporary variables that probe sorts, which is released
+/// before anything else can see it.
+const PROBE_SYMBOL: &[u8] = b"sort!probe\0";
 
 /// Sort reference.
 pub struct SortRef<'a> {
     solver: &'a Solver,
     sort_ptr: ffi::BoolectorSort,
+    // Known when the crate created the sort, otherwise probed on first use.
+    shape: OnceCell<SortShape>,
+}
+
+/// Widths that make up a sort; Boolector only builds arrays and functions
+/// over bit vectors.
+#[derive(Clone)]
+pub(crate) enum SortShape {
+    BitVec(u32),
+    Array(u32, u32),
+    Fun(Vec<u32>, u32),
 }
 
 impl<'a> SortRef<'a> {
     /// Return parent solver instance.
-    pub fn solver(&self) -> &Solver {
+    pub fn solver(&self) -> &'a Solver {
         self.solver
     }
 
@@ -31,31 +48,167 @@ impl<'a> SortRef<'a> {
         }
     }
 
+    /// Return whether this sort is a function sort; array sorts are not.
+    pub fn is_fun(&self) -> bool {
+        let is_fun = unsafe {
+            ffi::boolector_is_fun_sort(self.solver.btor_ptr(), self.sort_ptr)
+        };
+
+        is_fun && !self.is_array()
+    }
+
+    /// Return whether this sort is the boolean sort, which Boolector does not
+    /// distinguish from bit vectors of width 1.
+    pub fn is_bool(&self) -> bool {
+        self.is_bitvec() && self.width() == 1
+    }
+
+    /// Return the width of this bit vector sort.
+    ///
+    /// Panics if this is not a bit vector sort.
+    pub fn width(&self) -> u32 {
+        match self.shape() {
+            SortShape::BitVec(width) => *width,
+            _ => panic!("sort is not a bit vector sort"),
+        }
+    }
+
+    /// Return the index sort of this array sort.
+    ///
+    /// Panics if this is not an array sort.
+    pub fn index_sort(&self) -> SortRef<'a> {
+        match self.shape() {
+            SortShape::Array(index_width, _) => self.solver.sort(Sort::BitVec(*index_width)),
+            _ => panic!("sort is not an array sort"),
+        }
+    }
+
+    /// Return the element sort of this array sort.
+    ///
+    /// Panics if this is not an array sort.
+    pub fn element_sort(&self) -> SortRef<'a> {
+        match self.shape() {
+            SortShape::Array(_, element_width) => self.solver.sort(Sort::BitVec(*element_width)),
+            _ => panic!("sort is not an array sort"),
+        }
+    }
+
+    /// Return the argument sorts of this function sort.
+    ///
+    /// Panics if this is not a function sort.
+    ///
+    /// ```
+    /// use boolector::{Builder, Sort};
+    ///
+    /// let solver = Builder::new().finish();
+    /// let b8 = solver.sort(Sort::BitVec(8));
+    /// let b4 = solver.sort(Sort::BitVec(4));
+    /// let bool_sort = solver.sort(Sort::BitVec(1));
+    /// let fun = solver.sort(Sort::Fun(&[&b8, &b4], &bool_sort));
+    ///
+    /// assert!(fun.is_fun() && !fun.is_array());
+    /// assert_eq!(fun.domain(), vec![b8.clone(), b4]);
+    /// assert!(fun.codomain().is_bool());
+    /// assert_eq!(b8.width(), 8);
+    /// assert_ne!(b8, bool_sort);
+    /// ```
+    pub fn domain(&self) -> Vec<SortRef<'a>> {
+        match self.shape() {
+            SortShape::Fun(domain_widths, _) => {
+                domain_widths.iter().map(|&width| self.solver.sort(Sort::BitVec(width))).collect()
+            },
+            _ => panic!("sort is not a function sort"),
+        }
+    }
+
+    /// Return the result sort of this function sort.
+    ///
+    /// Panics if this is not a function sort.
+    pub fn codomain(&self) -> SortRef<'a> {
+        match self.shape() {
+            SortShape::Fun(_, codomain_width) => self.solver.sort(Sort::BitVec(*codomain_width)),
+            _ => panic!("sort is not a function sort"),
+        }
+    }
+
+    fn shape(&self) -> &SortShape {
+        self.shape.get_or_init(|| self.probe_shape())
+    }
+
+    /// Work out the widths of a sort that Boolector handed out, from a
+    /// temporary variable of that sort.
+    fn probe_shape(&self) -> SortShape {
+        let btor_ptr = self.solver.btor_ptr();
+
+        self.with_probe(|node_ptr| unsafe {
+            let width = ffi::boolector_get_width(btor_ptr, node_ptr);
+
+            if ffi::boolector_is_array(btor_ptr, node_ptr) {
+                SortShape::Array(ffi::boolector_get_index_width(btor_ptr, node_ptr), width)
+            } else if ffi::boolector_is_fun(btor_ptr, node_ptr) {
+                // The domain is a tuple sort, which the API cannot take
+                // apart, so read the argument sorts off the declaration.
+                let arity = ffi::boolector_get_fun_arity(btor_ptr, node_ptr);
+                let text = smt2::dump_node(btor_ptr, node_ptr).expect("cannot create temporary file");
+                let (_, signature) = smt2::declaration(&text).expect("unexpected declaration");
+                let domain_widths = smt2::domain_widths(signature);
+                assert_eq!(domain_widths.len(), arity as usize, "unexpected declaration");
+
+                SortShape::Fun(domain_widths, width)
+            } else {
+                SortShape::BitVec(width)
+            }
+        })
+    }
+
     /// Create a fresh variable of this sort and optionally associate a symbol
     /// with it.
+    ///
+    /// For array sorts this creates an array variable, and for function sorts
+    /// an uninterpreted function.  The solver instance keeps track of every
+    /// variable, see [Solver::vars](struct.Solver.html#method.vars).
+    ///
+    /// Boolector cannot create variables without a symbol once assertion
+    /// scopes have been used, so from then on such variables get a generated
+    /// symbol `unnamed!<n>`.
     pub fn var(&self, symbol: Option<&str>) -> NodeRef<'a> {
-        let cstr = symbol.and_then(|s| CString::new(s).ok());
+        let cstr = match symbol {
+            Some(s) => CString::new(s).ok(),
+            None => self.solver.unnamed_symbol(),
+        };
         let cstr_ptr = match &cstr {
             Some(s) => s.as_ptr(),
             None => ptr::null(),
         };
 
+        let var = unsafe {
+            NodeRef::wrap(self.solver, self.new_var(cstr_ptr))
+        };
+
+        self.solver.declare_var(&var);
+        var
+    }
+
+    /// Run `f` on a temporary variable of this sort with a fixed private
+    /// symbol, which is neither tracked nor handed out.
+    fn with_probe<T, F: FnOnce(*mut ffi::BoolectorNode) -> T>(&self, f: F) -> T {
+        unsafe {
+            let node_ptr = self.new_var(PROBE_SYMBOL.as_ptr() as *const c_char);
+            let result = f(node_ptr);
+            ffi::boolector_release(self.solver.btor_ptr(), node_ptr);
+            result
+        }
+    }
+
+    unsafe fn new_var(&self, symbol_ptr: *const c_char) -> *mut ffi::BoolectorNode {
+        let btor_ptr = self.solver.btor_ptr();
+
         if self.is_array() {
-            unsafe {
-                NodeRef::from_ffi(self.solver, ffi::boolector_array(
-                    self.solver.btor_ptr(),
-                    self.sort_ptr,
-                    cstr_ptr,
-                ))
-            }
+            ffi::boolector_array(btor_ptr, self.sort_ptr, symbol_ptr)
         } else if self.is_bitvec() {
-            unsafe {
-                NodeRef::from_ffi(self.solver, ffi::boolector_var(
-                    self.solver.btor_ptr(),
-                    self.sort_ptr,
-                    cstr_ptr,
-                ))
-            }
+            ffi::boolector_var(btor_ptr, self.sort_ptr, symbol_ptr)
+        } else if ffi::boolector_is_fun_sort(btor_ptr, self.sort_ptr) {
+            ffi::boolector_uf(btor_ptr, self.sort_ptr, symbol_ptr)
         } else {
             panic!("unexpected sort when creating a fresh variable");
         }
@@ -63,16 +216,36 @@ impl<'a> SortRef<'a> {
 
     /// Construct a sort reference from raw `BoolectorSort` value and its parent
     /// solver.
+    ///
+    /// This takes back a reference that left through
+    /// [into_ffi](#method.into_ffi), so that it no longer counts as leaked.
     pub unsafe fn from_ffi(solver: &'a Solver, sort_ptr: ffi::BoolectorSort) -> Self {
+        solver.reclaim_sort(sort_ptr);
+        SortRef::wrap(solver, sort_ptr)
+    }
+
+    /// Wrap a reference that the crate obtained from Boolector itself.
+    pub(crate) unsafe fn wrap(solver: &'a Solver, sort_ptr: ffi::BoolectorSort) -> Self {
         SortRef {
             solver,
             sort_ptr,
+            shape: OnceCell::new(),
+        }
+    }
+
+    /// Wrap a reference to a sort whose widths the crate already knows.
+    pub(crate) unsafe fn wrap_shaped(solver: &'a Solver, sort_ptr: ffi::BoolectorSort, shape: SortShape) -> Self {
+        SortRef {
+            solver,
+            sort_ptr,
+            shape: OnceCell::from(shape),
         }
     }
 
     /// Consume the sort reference and return the underlying `BoolectorSort`
     /// value.
     pub fn into_ffi(mut self) -> ffi::BoolectorSort {
+        self.solver.escape_sort(self.sort_ptr);
         mem::replace(&mut self.sort_ptr, ptr::null_mut())
     }
 
@@ -89,10 +262,50 @@ impl<'a> Clone for SortRef<'a> {
             sort_ptr: unsafe {
                 ffi::boolector_copy_sort(self.solver.btor_ptr(), self.sort_ptr)
             },
+            shape: self.shape.clone(),
         }
     }
 }
 
+/// Sorts are unique within a solver instance, so two references are equal if
+/// they belong to the same instance and hold the same sort pointer.
+impl<'a> PartialEq for SortRef<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.solver.btor_ptr() == other.solver.btor_ptr() && self.sort_ptr == other.sort_ptr
+    }
+}
+
+impl<'a> Eq for SortRef<'a> {}
+
+/// Formats the sort in SMT-LIB 2 syntax, such as `(_ BitVec 8)` or
+/// `(Array (_ BitVec 4) (_ BitVec 8))`; function sorts are written as
+/// `(-> (_ BitVec 8) (_ BitVec 1))`.
+impl<'a> fmt::Display for SortRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.shape() {
+            SortShape::BitVec(width) => write!(f, "(_ BitVec {})", width),
+            SortShape::Array(index_width, element_width) => {
+                write!(f, "(Array (_ BitVec {}) (_ BitVec {}))", index_width, element_width)
+            },
+            SortShape::Fun(domain_widths, codomain_width) => {
+                f.write_str("(->")?;
+
+                for width in domain_widths {
+                    write!(f, " (_ BitVec {})", width)?;
+                }
+
+                write!(f, " (_ BitVec {}))", codomain_width)
+            },
+        }
+    }
+}
+
+impl<'a> fmt::Debug for SortRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SortRef({})", self)
+    }
+}
+
 impl<'a> Drop for SortRef<'a> {
     fn drop(&mut self) {
         if !self.sort_ptr.is_null() {
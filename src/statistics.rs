@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+/// Snapshot of solver statistics.
+///
+/// Counters reported by Boolector are parsed from the output of
+/// `boolector_print_stats`; fields that the linked Boolector build does not
+/// report are left as `None`.  The remaining fields are maintained by this
+/// crate.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    /// Number of `solve` calls.
+    pub solve_calls: u64,
+
+    /// Wall times of `solve` calls, if enabled with
+    /// [Builder::record_solve_times](struct.Builder.html#method.record_solve_times).
+    pub solve_times: Option<SolveTimes>,
+
+    /// Number of SAT solver calls.
+    pub sat_calls: Option<u64>,
+
+    /// Number of lemmas generated by the lemmas on demand engine, which
+    /// Boolector only reports for formulas with functions or arrays.
+    pub lemmas: Option<u64>,
+
+    /// Number of synthesized nodes that were rewritten.
+    pub rewrites: Option<u64>,
+
+    /// Time spent in each solver phase, in the order reported by Boolector.
+    ///
+    /// Most phases are only timed if Boolector was built with time
+    /// statistics; SAT solving is always reported.
+    pub phase_times: Vec<(String, Duration)>,
+
+    /// Peak memory usage in megabytes.
+    pub memory_mb: Option<f64>,
+
+    /// All numeric statistics reported by Boolector, as (description, value)
+    /// pairs.
+    pub entries: Vec<(String, f64)>,
+}
+
+/// Aggregated wall times of `solve` calls.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SolveTimes {
+    /// Number of timed calls.
+    pub count: u64,
+
+    /// Sum of all call times.
+    pub total: Duration,
+
+    /// Shortest call.
+    pub min: Duration,
+
+    /// Longest call.
+    pub max: Duration,
+
+    /// Most recent call.
+    pub last: Duration,
+}
+
+impl SolveTimes {
+    /// Add the time of one call.
+    pub(crate) fn record(&mut self, time: Duration) {
+        self.min = if self.count == 0 { time } else { self.min.min(time) };
+        self.max = self.max.max(time);
+        self.total += time;
+        self.last = time;
+        self.count += 1;
+    }
+
+    /// Return the mean call time, or zero if no call was timed.
+    pub fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => self.total.div_f64(count as f64),
+        }
+    }
+}
+
+impl Statistics {
+    /// Parse the output of `boolector_print_stats`.
+    pub(crate) fn parse(output: &str) -> Self {
+        let mut stats = Statistics::default();
+
+        for line in output.lines() {
+            // Strip the message prefix, e.g. `[btor>core]`.
+            let line = match line.trim_start().strip_prefix('[') {
+                Some(rest) => match rest.find(']') {
+                    Some(end) => &rest[end + 1..],
+                    None => continue,
+                },
+                None => line,
+            };
+
+            let line = line.trim();
+            let (number, description) = match line.find(char::is_whitespace) {
+                Some(end) => (&line[..end], line[end..].trim()),
+                None => continue,
+            };
+
+            let value = match number.trim_end_matches('%').parse::<f64>() {
+                Ok(value) if value.is_finite() => value,
+                _ => continue,
+            };
+
+            if description.is_empty() {
+                continue;
+            }
+
+            stats.record(description, value);
+        }
+
+        stats
+    }
+
+    fn record(&mut self, description: &str, value: f64) {
+        let lowercase = description.to_lowercase();
+
+        if let Some((phase, seconds)) = phase_time(description, value) {
+            self.phase_times.push((phase.to_string(), Duration::from_secs_f64(seconds)));
+        }
+
+        // The last line is the peak memory usage; earlier lines such as
+        // `MB allocated for nodes` report parts of it.
+        if lowercase == "mb" {
+            self.memory_mb = Some(value);
+        } else if lowercase.starts_with("sat calls") {
+            self.sat_calls.get_or_insert(value as u64);
+        } else if lowercase == "lod refinements" {
+            self.lemmas.get_or_insert(value as u64);
+        } else if lowercase == "synthesized nodes rewritten" {
+            self.rewrites.get_or_insert(value as u64);
+        }
+
+        self.entries.push((description.to_string(), value));
+    }
+}
+
+/// Read a timing off a statistics line, which Boolector prints either as
+/// `<seconds> seconds <phase> (<percent>%)` or as `<count> <phase> in
+/// <seconds> seconds`.
+fn phase_time(description: &str, value: f64) -> Option<(&str, f64)> {
+    let (phase, seconds) = match description.strip_prefix("seconds ") {
+        Some(phase) => (phase, value),
+        None => {
+            let (phase, seconds) = description.strip_suffix(" seconds")?.rsplit_once(" in ")?;
+            (phase, seconds.parse().ok()?)
+        },
+    };
+
+    // Drop the share of the enclosing phase, e.g. `(12%)`.
+    let phase = match phase.rsplit_once(" (") {
+        Some((name, share)) if share.ends_with("%)") => name,
+        _ => phase,
+    };
+
+    let phase = phase.trim();
+
+    if phase.is_empty() || !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+
+    Some((phase, seconds))
+}
@@ -0,0 +1,56 @@
+use std::cell::{Cell, RefCell};
+use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+
+use boolector_sys as ffi;
+
+/// Termination state polled by Boolector during `solve`.
+///
+/// It is boxed by the owning `Solver` so that the address registered with
+/// `boolector_set_term` stays valid when the instance is moved.
+pub(crate) struct Termination {
+    callback: RefCell<Option<Box<dyn FnMut() -> bool + Send>>>,
+    deadline: Cell<Option<Instant>>,
+}
+
+impl Termination {
+    pub(crate) fn new() -> Box<Self> {
+        Box::new(Termination {
+            callback: RefCell::new(None),
+            deadline: Cell::new(None),
+        })
+    }
+
+    /// Register this state with a `Btor` instance.
+    pub(crate) unsafe fn attach(&self, btor_ptr: *mut ffi::Btor) {
+        ffi::boolector_set_term(btor_ptr, Some(poll), self as *const Self as *mut c_void);
+    }
+
+    pub(crate) fn set_callback(&self, callback: Option<Box<dyn FnMut() -> bool + Send>>) {
+        *self.callback.borrow_mut() = callback;
+    }
+
+    pub(crate) fn set_deadline(&self, deadline: Option<Instant>) {
+        self.deadline.set(deadline);
+    }
+
+    fn should_terminate(&self) -> bool {
+        if self.deadline.get().is_some_and(|deadline| Instant::now() >= deadline) {
+            return true;
+        }
+
+        match self.callback.try_borrow_mut() {
+            Ok(mut callback) => callback.as_mut().is_some_and(|callback| callback()),
+            Err(_) => false,
+        }
+    }
+}
+
+unsafe extern "C" fn poll(state: *mut c_void) -> i32 {
+    let state = &*(state as *const Termination);
+
+    // Unwinding into Boolector is undefined behavior; a panicking callback
+    // terminates the solver instead.
+    panic::catch_unwind(AssertUnwindSafe(|| state.should_terminate())).unwrap_or(true) as i32
+}
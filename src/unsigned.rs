@@ -1,5 +1,6 @@
 use std::ops;
 
+use crate::macros::Operand;
 use crate::{Node, NodeRef};
 
 /// Unsigned marker for `NodeRef`.
@@ -8,42 +9,36 @@ use crate::{Node, NodeRef};
 pub struct Unsigned<'a>(pub NodeRef<'a>);
 
 impl<'a> Unsigned<'a> {
-    pub fn lt(&'a self, other: &'a Self) -> NodeRef<'a> {
+    pub fn lt(&self, other: &Self) -> NodeRef<'a> {
         Node::ULt(&*self, &*other).into_ref(self.0.solver())
     }
 }
 
-impl<'a> ops::Add for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
-
-    fn add(self, other: Self) -> Self::Output {
-        Unsigned(Node::Add(&*self, &*other).into_ref(self.0.solver()))
+impl<'a> Operand<'a> for Unsigned<'a> {
+    fn node(&self) -> &NodeRef<'a> {
+        &self.0
     }
-}
-
-impl<'a> ops::BitAnd for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
 
-    fn bitand(self, other: Self) -> Self::Output {
-        Unsigned(Node::And(&*self, &*other).into_ref(self.0.solver()))
+    fn from_node(node: NodeRef<'a>) -> Self {
+        Unsigned(node)
     }
 }
 
-impl<'a> ops::BitOr for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
+impl_binary_op!(Unsigned, Add, add, AddAssign, add_assign, |a, b| {
+    Unsigned(Node::Add(&a.0, &b.0).into_ref(a.0.solver))
+});
 
-    fn bitor(self, other: Self) -> Self::Output {
-        Unsigned(Node::Or(&*self, &*other).into_ref(self.0.solver()))
-    }
-}
+impl_binary_op!(Unsigned, BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| {
+    Unsigned(Node::And(&a.0, &b.0).into_ref(a.0.solver))
+});
 
-impl<'a> ops::BitXor for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
+impl_binary_op!(Unsigned, BitOr, bitor, BitOrAssign, bitor_assign, |a, b| {
+    Unsigned(Node::Or(&a.0, &b.0).into_ref(a.0.solver))
+});
 
-    fn bitxor(self, other: Self) -> Self::Output {
-        Unsigned(Node::Xor(&*self, &*other).into_ref(self.0.solver()))
-    }
-}
+impl_binary_op!(Unsigned, BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| {
+    Unsigned(Node::Xor(&a.0, &b.0).into_ref(a.0.solver))
+});
 
 impl<'a> ops::Deref for Unsigned<'a> {
     type Target = NodeRef<'a>;
@@ -54,58 +49,30 @@ impl<'a> ops::Deref for Unsigned<'a> {
     }
 }
 
-impl<'a> ops::Div for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
+impl_binary_op!(Unsigned, Div, div, DivAssign, div_assign, |a, b| {
+    Unsigned(Node::UDiv(&a.0, &b.0).into_ref(a.0.solver))
+});
 
-    fn div(self, other: Self) -> Self::Output {
-        Unsigned(Node::UDiv(&*self, &*other).into_ref(self.0.solver()))
-    }
-}
+impl_binary_op!(Unsigned, Mul, mul, MulAssign, mul_assign, |a, b| {
+    Unsigned(Node::Mul(&a.0, &b.0).into_ref(a.0.solver))
+});
 
-impl<'a> ops::Mul for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
+impl_unary_op!(Unsigned, Not, not, |a| {
+    Unsigned(Node::Not(&a.0).into_ref(a.0.solver))
+});
 
-    fn mul(self, other: Self) -> Self::Output {
-        Unsigned(Node::Mul(&*self, &*other).into_ref(self.0.solver()))
-    }
-}
+impl_binary_op!(Unsigned, Rem, rem, RemAssign, rem_assign, |a, b| {
+    Unsigned(Node::URem(&a.0, &b.0).into_ref(a.0.solver))
+});
 
-impl<'a> ops::Not for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
-
-    fn not(self) -> Self::Output {
-        Unsigned(Node::Not(&*self).into_ref(self.0.solver()))
-    }
-}
+impl_binary_op!(Unsigned, Shl, shl, ShlAssign, shl_assign, |a, b| {
+    Unsigned(a.0.shift_left(&b.0))
+});
 
-impl<'a> ops::Rem for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
+impl_binary_op!(Unsigned, Shr, shr, ShrAssign, shr_assign, |a, b| {
+    Unsigned(a.0.shift_right_logical(&b.0))
+});
 
-    fn rem(self, other: Self) -> Self::Output {
-        Unsigned(Node::URem(&*self, &*other).into_ref(self.0.solver()))
-    }
-}
-
-impl<'a> ops::Shl for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
-
-    fn shl(self, other: Self) -> Self::Output {
-        Unsigned(Node::Sll(&*self, &*other).into_ref(self.0.solver()))
-    }
-}
-
-impl<'a> ops::Shr for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
-
-    fn shr(self, other: Self) -> Self::Output {
-        Unsigned(Node::Srl(&*self, &*other).into_ref(self.0.solver()))
-    }
-}
-
-impl<'a> ops::Sub for &'a Unsigned<'a> {
-    type Output = Unsigned<'a>;
-
-    fn sub(self, other: Self) -> Self::Output {
-        Unsigned(Node::Sub(&*self, &*other).into_ref(self.0.solver()))
-    }
-}
+impl_binary_op!(Unsigned, Sub, sub, SubAssign, sub_assign, |a, b| {
+    Unsigned(Node::Sub(&a.0, &b.0).into_ref(a.0.solver))
+});
@@ -0,0 +1,41 @@
+use crate::{ArrayValue, BitVecValue};
+
+/// Concrete value of an expression, see [eval](fn.eval.html).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Value {
+    /// Bit vector value; booleans are 1-bit vectors.
+    BitVec(BitVecValue),
+
+    /// Array value.
+    Array(ArrayValue),
+}
+
+impl Value {
+    /// Return the bit vector value, or `None` for arrays.
+    pub fn as_bit_vec(&self) -> Option<&BitVecValue> {
+        match self {
+            Value::BitVec(value) => Some(value),
+            Value::Array(_) => None,
+        }
+    }
+
+    /// Return the array value, or `None` for bit vectors.
+    pub fn as_array(&self) -> Option<&ArrayValue> {
+        match self {
+            Value::BitVec(_) => None,
+            Value::Array(value) => Some(value),
+        }
+    }
+}
+
+impl From<BitVecValue> for Value {
+    fn from(value: BitVecValue) -> Self {
+        Value::BitVec(value)
+    }
+}
+
+impl From<ArrayValue> for Value {
+    fn from(value: ArrayValue) -> Self {
+        Value::Array(value)
+    }
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "serde")]
+
+use std::ffi::CString;
+
+use boolector::{Builder, GenerateModel, ModelSnapshot, Node, NodeRef, SnapshotValue, SolveResult, Solver, Sort, SortRef};
+use boolector_sys as ffi;
+
+fn uf<'a>(solver: &'a Solver, sort: &SortRef<'a>, symbol: &str) -> NodeRef<'a> {
+    let symbol = CString::new(symbol).unwrap();
+
+    unsafe {
+        NodeRef::from_ffi(solver, ffi::boolector_uf(solver.btor_ptr(), sort.sort_ptr(), symbol.as_ptr()))
+    }
+}
+
+#[test]
+fn snapshot_round_trip() {
+    let solver = Builder::new().generate_model(GenerateModel::All).finish();
+    let b8 = solver.sort(Sort::BitVec(8));
+    let b4 = solver.sort(Sort::BitVec(4));
+    let array_sort = solver.sort(Sort::Array(&b4, &b8));
+    let fun_sort = solver.sort(Sort::Fun(&[&b8, &b4], &b8));
+
+    let x = b8.var(Some("x"));
+    let i = b4.var(Some("i"));
+    let a = array_sort.var(Some("a"));
+    let f = uf(&solver, &fun_sort, "f");
+
+    solver.assert(&solver.node(Node::Eq(&(&x * 5), &(&x + 12))));
+    solver.assert(&solver.node(Node::Eq(&solver.node(Node::UExt(&i, 4)), &(&x - 1))));
+
+    let snapshot = match solver.solve() {
+        SolveResult::Sat(Some(model)) => model.snapshot(&[&x, &i, &a, &f]),
+        _ => panic!("expected a model"),
+    };
+
+    // A function for which the model fixes no result is kept, with its
+    // widths.
+    assert_eq!(
+        snapshot.get("f"),
+        Some(&SnapshotValue::Fun {
+            domain_widths: vec![8, 4],
+            codomain_width: 8,
+            entries: Vec::new(),
+        })
+    );
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let parsed: ModelSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, snapshot);
+    assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+
+    let replay = Builder::new().generate_model(GenerateModel::All).finish();
+    let vars = parsed.assert_into(&replay);
+    assert_eq!(vars.len(), 4);
+    assert!(unsafe {
+        ffi::boolector_is_array(replay.btor_ptr(), vars["a"].node_ptr())
+            && ffi::boolector_is_uf(replay.btor_ptr(), vars["f"].node_ptr())
+    });
+
+    replay.assert(&replay.node(Node::Eq(&(&vars["x"] * 5), &(&vars["x"] + 12))));
+
+    match replay.solve() {
+        SolveResult::Sat(Some(model)) => {
+            let again = model.snapshot(&[&vars["x"], &vars["i"]]);
+            assert_eq!(again.get("x"), snapshot.get("x"));
+            assert_eq!(again.get("i"), snapshot.get("i"));
+        },
+        _ => panic!("expected the snapshot to satisfy the assertions"),
+    }
+}
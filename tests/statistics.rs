@@ -0,0 +1,70 @@
+use std::ffi::CString;
+
+use boolector::{Builder, GenerateModel, Node, NodeRef, Solver, Sort, SortRef};
+use boolector_sys as ffi;
+
+fn constant<'a>(solver: &'a Solver, sort: &SortRef<'a>, value: u32) -> NodeRef<'a> {
+    unsafe {
+        NodeRef::from_ffi(solver, ffi::boolector_unsigned_int(solver.btor_ptr(), value, sort.sort_ptr()))
+    }
+}
+
+fn uf<'a>(solver: &'a Solver, sort: &SortRef<'a>, symbol: &str) -> NodeRef<'a> {
+    let symbol = CString::new(symbol).unwrap();
+
+    unsafe {
+        NodeRef::from_ffi(solver, ffi::boolector_uf(solver.btor_ptr(), sort.sort_ptr(), symbol.as_ptr()))
+    }
+}
+
+fn apply<'a>(solver: &'a Solver, fun: &NodeRef<'a>, arg: &NodeRef<'a>) -> NodeRef<'a> {
+    let mut args = [arg.node_ptr()];
+
+    unsafe {
+        NodeRef::from_ffi(solver, ffi::boolector_apply(solver.btor_ptr(), args.as_mut_ptr(), 1, fun.node_ptr()))
+    }
+}
+
+#[test]
+fn statistics_from_a_real_solve() {
+    let solver = Builder::new().generate_model(GenerateModel::Asserted).finish();
+    let b16 = solver.sort(Sort::BitVec(16));
+    let domain = [&b16];
+    let fun_sort = solver.sort(Sort::Fun(&domain, &b16));
+    let f = uf(&solver, &fun_sort, "f");
+    let x = b16.var(Some("x"));
+    let y = b16.var(Some("y"));
+    let one = constant(&solver, &b16, 1);
+
+    // Factor a semiprime, and make the function disagree on the factors.
+    let product = constant(&solver, &b16, 30031);
+    let xy = solver.node(Node::Mul(&x, &y));
+    let factored = solver.node(Node::Eq(&xy, &product));
+    let x_gt_one = solver.node(Node::UGt(&x, &one));
+    let y_gt_one = solver.node(Node::UGt(&y, &one));
+    solver.assert(&factored);
+    solver.assert(&x_gt_one);
+    solver.assert(&y_gt_one);
+
+    let fx = apply(&solver, &f, &x);
+    let fy = apply(&solver, &f, &y);
+    let differ = solver.node(Node::Ne(&fx, &fy));
+    solver.assert(&differ);
+
+    assert!(solver.solve().is_sat());
+
+    let stats = solver.statistics();
+
+    // The peak memory usage is the final `MB` line, not one of the earlier
+    // lines that report parts of it.
+    let peak = stats.entries.iter().rev().find(|(description, _)| description == "MB").map(|&(_, value)| value);
+    assert!(peak.is_some());
+    assert_eq!(stats.memory_mb, peak);
+    assert!(stats.memory_mb.unwrap() > 0.0);
+
+    assert!(!stats.phase_times.is_empty());
+    assert!(stats.phase_times.iter().any(|(phase, _)| phase == "SAT calls"));
+    assert!(stats.sat_calls.unwrap() >= 1);
+    assert!(stats.lemmas.is_some());
+    assert!(stats.rewrites.is_some());
+}